@@ -1,11 +1,14 @@
 import type { College } from "./College";
+import type { Event } from "./Event";
 import type { Expense } from "./Expense";
 import type { Hsa } from "./Hsa";
 import type { Income } from "./Income";
 import type { Loan } from "./Loan";
 import type { Mortgage } from "./Mortgage";
+import type { Pension } from "./Pension";
+import type { RealEstate } from "./RealEstate";
 import type { Retirement } from "./Retirement";
 import type { Savings } from "./Savings";
 import type { Ssa } from "./Ssa";
 
-export type AccountWrapperUI = { type: "income" } & Income<number> | { type: "ssa" } & Ssa | { type: "retirement" } & Retirement<number> | { type: "hsa" } & Hsa<number> | { type: "college" } & College<number> | { type: "expense" } & Expense<number> | { type: "loan" } & Loan<number> | { type: "mortgage" } & Mortgage<number> | { type: "savings" } & Savings<number>;
\ No newline at end of file
+export type AccountWrapperUI = { type: "income" } & Income<number> | { type: "pension" } & Pension<number> | { type: "event" } & Event<number> | { type: "ssa" } & Ssa | { type: "retirement" } & Retirement<number> | { type: "hsa" } & Hsa<number> | { type: "college" } & College<number> | { type: "expense" } & Expense<number> | { type: "loan" } & Loan<number> | { type: "mortgage" } & Mortgage<number> | { type: "realEstate" } & RealEstate<number> | { type: "savings" } & Savings<number>;
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface Amortization { term: number, extraPrincipal: number, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface AmortizationSummary { payoffYear: number, payoffMonth: number, totalInterest: number, interestSaved: number, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+import type { BacktestRun } from "./BacktestRun";
+
+export interface BacktestResult { runs: Array<BacktestRun>, worst: BacktestRun | null, median: BacktestRun | null, best: BacktestRun | null, failures: number, errors: number, }
\ No newline at end of file
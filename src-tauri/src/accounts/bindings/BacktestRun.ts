@@ -0,0 +1,2 @@
+
+export interface BacktestRun { startYear: number, success: boolean, errored: boolean, endingSaving: number, endingNet: number, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface CashSweep { cashBuffer: number, depositAccounts: Array<string>, withdrawalOrder: Array<string>, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface ContributionLimit { limit: number, catchUp: number, catchUpAge: number, }
\ No newline at end of file
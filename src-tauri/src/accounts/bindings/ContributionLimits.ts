@@ -0,0 +1,3 @@
+import type { ContributionLimit } from "./ContributionLimit";
+
+export interface ContributionLimits { workplace: ContributionLimit, ira: ContributionLimit, hsa: ContributionLimit, }
\ No newline at end of file
@@ -1,2 +1,2 @@
 
-export type ContributionOptions = "fixed" | "percent_of_income" | "fixed_with_inflation";
\ No newline at end of file
+export type ContributionOptions = "fixed" | "percent_of_income" | "fixed_with_inflation" | "max_allowed";
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface ConversionComparison { lifetimeTaxes: number, lifetimeTaxesWithoutConversions: number, endingSaving: number, endingSavingWithoutConversions: number, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type ConversionOptions = "fixed" | "fixed_with_inflation" | "fill_bracket";
\ No newline at end of file
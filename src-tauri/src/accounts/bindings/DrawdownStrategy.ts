@@ -0,0 +1,2 @@
+
+export type DrawdownStrategy = "taxable_first" | "proportional" | "fill_low_bracket";
\ No newline at end of file
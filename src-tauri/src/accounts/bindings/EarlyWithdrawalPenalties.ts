@@ -0,0 +1,3 @@
+import type { EarlyWithdrawalPenalty } from "./EarlyWithdrawalPenalty";
+
+export interface EarlyWithdrawalPenalties { retirement: EarlyWithdrawalPenalty, hsa: EarlyWithdrawalPenalty, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface EarlyWithdrawalPenalty { age: number, rate: number, }
\ No newline at end of file
@@ -1,3 +1,3 @@
-import type { PercentInput } from "./PercentInput";
+import type { MatchTier } from "./MatchTier";
 
-export interface EmployerMatch { amount: PercentInput, limit: PercentInput, }
\ No newline at end of file
+export type EmployerMatch = Array<MatchTier> | MatchTier;
\ No newline at end of file
@@ -0,0 +1,3 @@
+import type { PercentInput } from "./PercentInput";
+
+export interface Escrow { assessedValue: number, assessmentGrowth: PercentInput, propertyTaxRate: number, insurance: number, insuranceInflation: PercentInput, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+import type { EventTax } from "./EventTax";
+import type { Table } from "./Table";
+import type { YearInput } from "./YearInput";
+
+export interface Event<T> { name: string, table: Table<T>, year: YearInput, amount: number, taxTreatment: EventTax, costBasis: number | null, destination: string | null, notes: string | null, }
\ No newline at end of file
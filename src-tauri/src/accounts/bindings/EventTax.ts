@@ -0,0 +1,2 @@
+
+export type EventTax = "untaxed" | "income" | "capital_gains";
\ No newline at end of file
@@ -1,5 +1,6 @@
+import type { Owner } from "./Owner";
 import type { PercentInput } from "./PercentInput";
 import type { Table } from "./Table";
 import type { YearInput } from "./YearInput";
 
-export interface Income<T> { name: string, table: Table<T>, base: number, startIn: YearInput, endIn: YearInput, raise: PercentInput, notes: string | null, }
\ No newline at end of file
+export interface Income<T> { name: string, table: Table<T>, base: number, startIn: YearInput, endIn: YearInput, raise: PercentInput, owner: Owner | null, notes: string | null, }
\ No newline at end of file
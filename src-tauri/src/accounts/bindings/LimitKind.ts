@@ -0,0 +1,2 @@
+
+export type LimitKind = "workplace" | "ira" | "hsa";
\ No newline at end of file
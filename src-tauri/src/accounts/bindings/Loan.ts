@@ -1,6 +1,7 @@
+import type { LoanChange } from "./LoanChange";
 import type { PaymentOptions } from "./PaymentOptions";
 import type { PercentInput } from "./PercentInput";
 import type { Table } from "./Table";
 import type { YearInput } from "./YearInput";
 
-export interface Loan<T> { name: string, table: Table<T>, startOut: YearInput, endOut: YearInput, paymentType: PaymentOptions, paymentValue: number, rate: PercentInput, notes: string | null, }
\ No newline at end of file
+export interface Loan<T> { name: string, table: Table<T>, startOut: YearInput, endOut: YearInput, paymentType: PaymentOptions, paymentValue: number, term: number | null, rate: PercentInput, changes: Array<LoanChange> | null, notes: string | null, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+import type { PercentInput } from "./PercentInput";
+import type { RateCaps } from "./RateCaps";
+import type { YearInput } from "./YearInput";
+
+export interface LoanChange { year: YearInput, rate: PercentInput | null, term: number | null, caps: RateCaps | null, closingCosts: number, }
\ No newline at end of file
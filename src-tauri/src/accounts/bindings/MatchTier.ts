@@ -0,0 +1,3 @@
+import type { PercentInput } from "./PercentInput";
+
+export interface MatchTier { amount: PercentInput, limit: PercentInput, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+import type { ReturnDistribution } from "./ReturnDistribution";
+import type { ReturnModel } from "./ReturnModel";
+
+export interface MonteCarloOptions { runs: number, seed: number, assetClasses: Record<string, ReturnDistribution>, accounts: Record<string, ReturnModel>, }
\ No newline at end of file
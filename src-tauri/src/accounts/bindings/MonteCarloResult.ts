@@ -0,0 +1,3 @@
+import type { PercentileBands } from "./PercentileBands";
+
+export interface MonteCarloResult { runs: number, successes: number, errors: number, successProbability: number, years: Array<number>, saving: PercentileBands, net: PercentileBands, }
\ No newline at end of file
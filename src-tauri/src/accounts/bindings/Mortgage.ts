@@ -1,6 +1,9 @@
+import type { Amortization } from "./Amortization";
+import type { Escrow } from "./Escrow";
+import type { LoanChange } from "./LoanChange";
 import type { PaymentOptions } from "./PaymentOptions";
 import type { PercentInput } from "./PercentInput";
 import type { Table } from "./Table";
 import type { YearInput } from "./YearInput";
 
-export interface Mortgage<T> { name: string, table: Table<T>, startOut: YearInput, endOut: YearInput, paymentType: PaymentOptions, paymentValue: number, rate: PercentInput, compoundTime: number, mortgageInsurance: number, ltvLimit: number, escrowValue: number, homeValue: number, notes: string | null, }
\ No newline at end of file
+export interface Mortgage<T> { name: string, table: Table<T>, startOut: YearInput, endOut: YearInput, paymentType: PaymentOptions, paymentValue: number, term: number | null, rate: PercentInput, changes: Array<LoanChange> | null, compoundTime: number, mortgageInsurance: number, ltvLimit: number, escrowValue: number, escrow: Escrow | null, homeValue: number, propertyLink: string | null, amortization: Amortization | null, notes: string | null, }
\ No newline at end of file
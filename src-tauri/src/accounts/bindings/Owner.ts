@@ -0,0 +1,2 @@
+
+export type Owner = "primary" | "spouse";
\ No newline at end of file
@@ -1,2 +1,2 @@
 
-export type PaymentOptions = "fixed" | "fixed_with_inflation";
\ No newline at end of file
+export type PaymentOptions = "fixed" | "fixed_with_inflation" | "amortized";
\ No newline at end of file
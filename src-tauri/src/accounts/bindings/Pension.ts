@@ -0,0 +1,6 @@
+import type { Owner } from "./Owner";
+import type { PercentInput } from "./PercentInput";
+import type { Table } from "./Table";
+import type { YearInput } from "./YearInput";
+
+export interface Pension<T> { name: string, table: Table<T>, startIn: YearInput, benefit: number, cola: PercentInput | null, survivorPercent: number, lumpSum: number | null, owner: Owner | null, notes: string | null, }
\ No newline at end of file
@@ -1,3 +1,4 @@
 import type { PercentSuggestions } from "./PercentSuggestions";
+import type { RateSchedule } from "./RateSchedule";
 
-export type PercentInput = PercentSuggestions | number | string;
\ No newline at end of file
+export type PercentInput = PercentSuggestions | number | string | RateSchedule;
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface PercentileBands { p10: Array<number>, p50: Array<number>, p90: Array<number>, }
\ No newline at end of file
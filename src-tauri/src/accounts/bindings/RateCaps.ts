@@ -0,0 +1,2 @@
+
+export interface RateCaps { adjustment: number, lifetime: number, }
\ No newline at end of file
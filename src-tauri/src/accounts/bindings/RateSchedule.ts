@@ -0,0 +1,4 @@
+import type { ScheduleKey } from "./ScheduleKey";
+import type { ScheduleStep } from "./ScheduleStep";
+
+export interface RateSchedule { key: ScheduleKey, steps: Array<ScheduleStep>, interpolate: boolean, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+import type { PercentInput } from "./PercentInput";
+import type { Table } from "./Table";
+
+export interface RealEstate<T> { name: string, table: Table<T>, purchasePrice: number, appreciation: PercentInput, notes: string | null, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface ReturnDistribution { mean: number, volatility: number, }
\ No newline at end of file
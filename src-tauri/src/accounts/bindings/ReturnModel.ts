@@ -0,0 +1,3 @@
+import type { ReturnDistribution } from "./ReturnDistribution";
+
+export type ReturnModel = string | ReturnDistribution;
\ No newline at end of file
@@ -0,0 +1,4 @@
+import type { ConversionOptions } from "./ConversionOptions";
+import type { YearInput } from "./YearInput";
+
+export interface RothConversion { fromAccount: string, toAccount: string, startYear: YearInput, endYear: YearInput, conversionType: ConversionOptions, conversionValue: number, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type ScheduleKey = "year" | "age";
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface ScheduleStep { at: number, rate: number, }
\ No newline at end of file
@@ -1,3 +1,11 @@
+import type { CashSweep } from "./CashSweep";
+import type { ContributionLimits } from "./ContributionLimits";
+import type { DrawdownStrategy } from "./DrawdownStrategy";
+import type { EarlyWithdrawalPenalties } from "./EarlyWithdrawalPenalties";
+import type { RateSchedule } from "./RateSchedule";
+import type { RothConversion } from "./RothConversion";
+import type { Spouse } from "./Spouse";
 import type { SsaSettings } from "./SsaSettings";
+import type { TaxBrackets } from "./TaxBrackets";
 
-export interface Settings { ageRetire: number, ageDie: number, yearBorn: number, yearStart: number, inflationBase: number, taxIncome: number, taxCapitalGains: number, retirementCostOfLiving: number, ssa: SsaSettings, }
\ No newline at end of file
+export interface Settings { ageRetire: number, ageDie: number, yearBorn: number, yearStart: number, inflationBase: number, taxIncome: number, taxCapitalGains: number, retirementCostOfLiving: number, ssa: SsaSettings, spouse: Spouse | null, taxBrackets: TaxBrackets | null, cashSweep: CashSweep | null, drawdown: DrawdownStrategy | null, rothConversion: RothConversion | null, contributionLimits: ContributionLimits | null, earlyWithdrawalPenalties: EarlyWithdrawalPenalties | null, inflationSchedule: RateSchedule | null, }
\ No newline at end of file
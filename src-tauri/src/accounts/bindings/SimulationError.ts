@@ -0,0 +1,2 @@
+
+export type SimulationError = { type: "init", uuid: string, name: string, cause: string, } | { type: "simulate", uuid: string, name: string, year: number, cause: string, } | { type: "missingLink", uuid: string, name: string, linkId: string, } | { type: "missingAccount", uuid: string, } | { type: "invalidOptions", cause: string, };
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface Spouse { ageRetire: number, ageDie: number, yearBorn: number, }
\ No newline at end of file
@@ -1,3 +1,4 @@
+import type { Owner } from "./Owner";
 import type { YearInput } from "./YearInput";
 
-export interface Ssa { name: string, base: number, startIn: YearInput, endIn: YearInput, notes: string | null, }
\ No newline at end of file
+export interface Ssa { name: string, base: number, startIn: YearInput, endIn: YearInput, owner: Owner | null, spouseLink: string | null, notes: string | null, }
\ No newline at end of file
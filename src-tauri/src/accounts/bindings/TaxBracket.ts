@@ -0,0 +1,2 @@
+
+export interface TaxBracket { threshold: number, rate: number, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+import type { TaxBracket } from "./TaxBracket";
+
+export interface TaxBrackets { brackets: Array<TaxBracket>, standardDeduction: number, }
\ No newline at end of file
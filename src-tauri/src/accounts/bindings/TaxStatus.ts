@@ -1,2 +1,2 @@
 
-export type TaxStatus = "contribute_taxed_earnings_untaxed_when_used" | "contribute_taxed_earnings_taxed" | "contribute_pretax_taxed_when_used" | "contribute_pretax_untaxed_when_used";
\ No newline at end of file
+export type TaxStatus = "contribute_taxed_earnings_untaxed_when_used" | "contribute_taxed_earnings_taxed" | "contribute_taxed_earnings_taxed_when_used" | "contribute_pretax_taxed_when_used" | "contribute_pretax_untaxed_when_used";
\ No newline at end of file
@@ -1,2 +1,2 @@
 
-export type WithdrawalOptions = "fixed" | "fixed_with_inflation" | "end_at_zero" | "col_frac_of_savings" | "other";
\ No newline at end of file
+export type WithdrawalOptions = "fixed" | "fixed_with_inflation" | "end_at_zero" | "col_frac_of_savings" | "other" | "planned";
\ No newline at end of file
@@ -1,2 +1,2 @@
 
-export type YearSuggestion = "yearStart" | "yearRetire" | "yearDie" | "yearEnd" | "incomeLink";
\ No newline at end of file
+export type YearSuggestion = "yearStart" | "yearRetire" | "yearDie" | "spouseYearRetire" | "spouseYearDie" | "yearEnd" | "incomeLink";
\ No newline at end of file
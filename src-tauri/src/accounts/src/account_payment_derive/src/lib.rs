@@ -23,6 +23,7 @@ fn impl_payment_type(ast: &syn::DeriveInput) -> TokenStream {
                     PaymentOptions::FixedWithInflation => {
                         fixed_with_inflation(self.payment_value, year, settings)
                     }
+                    PaymentOptions::Amortized => self.amortized_payment,
                 };
                 let outstanding_balance = self.analysis.value.get(year).unwrap();
                 if output > outstanding_balance {
@@ -31,6 +31,16 @@ fn impl_savings_type(ast: &syn::DeriveInput) -> TokenStream {
                         // increase the value by inflation
                         fixed_with_inflation(self.contribution_value, year, settings)
                     }
+                    ContributionOptions::MaxAllowed => {
+                        // contribute what is left of the limit for the account (accounts without a limit are rejected before simulating)
+                        self.contribution_room(year, totals, settings).unwrap_or_default()
+                    }
+                }
+            }
+            fn get_yearly_return(&self, year:u32, settings: &Settings ) -> f64 {
+                match &self.return_sequence {
+                    Some(returns) => returns.get(year).unwrap_or_default(),
+                    None => self.yearly_return.value(year, self.owner(), settings),
                 }
             }
             fn get_withdrawal(&self, year:u32, totals: &YearlyTotals, settings: &Settings ) -> f64 {
@@ -38,6 +48,10 @@ fn impl_savings_type(ast: &syn::DeriveInput) -> TokenStream {
                     WithdrawalOptions::Other => {
                         0_f64
                     }
+                    WithdrawalOptions::Planned => {
+                        // withdrawals are made by the drawdown planner after all accounts are simulated
+                        0_f64
+                    }
                     WithdrawalOptions::Fixed => {
                         self.withdrawal_value
                     }
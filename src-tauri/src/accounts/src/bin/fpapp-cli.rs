@@ -0,0 +1,125 @@
+//! Headless runner for financial plan files
+//!
+//! Loads one or more user data files (the same json format the application opens and saves),
+//! runs the simulation on each, and writes the results to an output folder per plan.
+//!
+//! ```text
+//! fpapp-cli [--out <dir>] [--no-plots] <plan.json>...
+//! ```
+
+use log::{error, info};
+use std::fs::{create_dir_all, read_to_string};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+use accounts::{AccountWrapper, UserData};
+
+/// Width of the plots written for each account
+const PLOT_WIDTH: u32 = 1600;
+/// Height of the plots written for each account
+const PLOT_HEIGHT: u32 = 1200;
+
+/// Options parsed from the command line
+struct Options {
+    /// Folder to write results into (each plan gets its own sub folder)
+    out_dir: PathBuf,
+    /// Write png plots in addition to csv files
+    plots: bool,
+    /// Plan files to run
+    files: Vec<PathBuf>,
+}
+
+impl Options {
+    /// Parse command line arguments
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            out_dir: PathBuf::from("."),
+            plots: true,
+            files: Vec::new(),
+        };
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-o" | "--out" => match args.next() {
+                    Some(dir) => options.out_dir = PathBuf::from(dir),
+                    None => return Err(format!("Missing folder after {}", arg)),
+                },
+                "--no-plots" => options.plots = false,
+                "-h" | "--help" => return Err(String::new()),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+                _ => options.files.push(PathBuf::from(arg)),
+            }
+        }
+        if options.files.is_empty() {
+            return Err(String::from("No plan files provided"));
+        }
+        Ok(options)
+    }
+}
+
+/// Load a plan file from disk
+fn load(path: &Path) -> Result<UserData<AccountWrapper>, String> {
+    let json_file_str = read_to_string(path)
+        .map_err(|e| format!("Unable to open file {} {}", path.display(), e))?;
+    serde_json::from_str::<UserData<AccountWrapper>>(&json_file_str)
+        .map_err(|e| format!("Unable to process input data file {} {}", path.display(), e))
+}
+
+/// Run the simulation for a plan file and write the results into `out_dir`
+fn run_plan(path: &Path, out_dir: &Path, plots: bool) -> Result<(), String> {
+    let input = load(path)?;
+    create_dir_all(out_dir)
+        .map_err(|e| format!("Unable to create folder {} {}", out_dir.display(), e))?;
+
+    let results = accounts::run(input.into()).map_err(|e| e.to_string())?;
+
+    let out = |name: &str| out_dir.join(name).to_string_lossy().to_string();
+
+    results.totals.write_summary(out("summary.csv"));
+    results
+        .data
+        .write_tables(&results.account_order, results.years.clone(), out("tables.csv"));
+    for uuid in results.account_order.iter() {
+        let account = &results.data.accounts[uuid];
+        account.write(out(&format!("{}.csv", uuid)));
+        if plots {
+            account.plot_to_file(out(&format!("{}.png", uuid)), PLOT_WIDTH, PLOT_HEIGHT);
+        }
+    }
+    if plots {
+        results.totals.plot_to_file(out("summary.png"));
+    }
+    Ok(())
+}
+
+fn main() {
+    env_logger::init();
+
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("{}", message);
+            }
+            eprintln!("Usage: fpapp-cli [--out <dir>] [--no-plots] <plan.json>...");
+            exit(2);
+        }
+    };
+
+    let mut failures = 0;
+    for file in options.files.iter() {
+        let stem = file.file_stem().unwrap_or_default();
+        let out_dir = options.out_dir.join(stem);
+        match run_plan(file, &out_dir, options.plots) {
+            Ok(()) => info!("{} -> {}", file.display(), out_dir.display()),
+            Err(e) => {
+                error!("{}", e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        exit(1);
+    }
+}
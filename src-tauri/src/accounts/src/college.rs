@@ -53,6 +53,9 @@ pub struct College<T: std::cmp::Ord> {
     /// Calculated date values as a year based on input values
     #[serde(skip)]
     dates: Dates,
+    /// Percent interest earned in each year used instead of yearly_return (set for Monte Carlo runs)
+    #[serde(skip)]
+    return_sequence: Option<Table<u32>>,
 }
 
 impl From<College<String>> for College<u32> {
@@ -76,6 +79,7 @@ impl From<College<String>> for College<u32> {
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
+            return_sequence: other.return_sequence,
         }
     }
 }
@@ -149,6 +153,9 @@ impl Account for College<u32> {
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year)
+    }
     fn get_inputs(&self) -> String {
         String::from("Hello")
     }
@@ -182,9 +189,16 @@ impl Account for College<u32> {
     fn get_plot_data(&self) -> Vec<PlotDataSet> {
         self.analysis.get_plot_data()
     }
+    fn set_return_sequence(&mut self, returns: Table<u32>) {
+        self.return_sequence = Some(returns);
+    }
+    fn contribution_type(&self) -> Option<ContributionOptions> {
+        Some(self.contribution_type)
+    }
     fn simulate(
         &mut self,
         year: u32,
+        _linked_value: Option<f64>,
         totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -198,7 +212,7 @@ impl Account for College<u32> {
 
         // Calculate earnings
         result.earning =
-            self.analysis.value.get(year).unwrap() * (self.yearly_return.value(settings) / 100.0); // calculate earnings from interest
+            self.analysis.value.get(year).unwrap() * (self.get_yearly_return(year, settings) / 100.0); // calculate earnings from interest
 
         // Add earnings to earnings and value tables
         self.analysis.earnings.update(year, result.earning);
@@ -231,13 +245,19 @@ impl Account for College<u32> {
                 col: 0_f64,
                 saving: 0_f64,
                 income_taxable: 0_f64,
+                capital_gains: 0_f64,
                 income: 0_f64,
                 hsa: 0_f64,
+                penalty: 0_f64,
             }),
             TaxStatus::ContributeTaxedEarningsTaxed => Err(String::from(
                 "This tax status type is not implemented for college accounts.",
             )
             .into()),
+            TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => Err(String::from(
+                "This tax status type is not implemented for college accounts.",
+            )
+            .into()),
             TaxStatus::ContributePretaxTaxedWhenUsed => Err(String::from(
                 "This tax status type is not implemented for college accounts.",
             )
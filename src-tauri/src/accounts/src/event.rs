@@ -0,0 +1,284 @@
+//! One time event such as an inheritance, a home sale, a bonus or a large purchase
+
+use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use ts_rs::TS;
+
+use super::*;
+use crate::inputs::EventTax;
+
+/// Account to represent money received or spent once
+#[derive(TS, Debug, Clone, Deserialize, Serialize)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Event<T: std::cmp::Ord> {
+    /// String describing this account
+    name: String,
+    /// Table of event amounts
+    table: Table<T>,
+    /// Calendar year when the event happens
+    year: YearInput,
+    /// Amount received (positive) or spent (negative) in the year of the event
+    amount: f64,
+    /// How money received from the event is taxed
+    tax_treatment: EventTax,
+    /// Part of the amount received that is not taxed (such as the price paid for a sold home)
+    cost_basis: Option<f64>,
+    /// Account the money is deposited in or withdrawn from instead of net (can not be pretax)
+    destination: Option<String>,
+    /// General information to store with this account
+    notes: Option<String>,
+    // The following items are used when running the program and are not stored with the user data
+    /// Tables used to store simulation results
+    #[serde(skip)]
+    analysis: SingleTable,
+    /// Calculated date values as a year based on input values
+    #[serde(skip)]
+    dates: Dates,
+}
+
+impl From<Event<String>> for Event<u32> {
+    fn from(other: Event<String>) -> Self {
+        Self {
+            name: other.name,
+            table: other.table.into(),
+            year: other.year,
+            amount: other.amount,
+            tax_treatment: other.tax_treatment,
+            cost_basis: other.cost_basis,
+            destination: other.destination,
+            notes: other.notes,
+            analysis: other.analysis,
+            dates: other.dates,
+        }
+    }
+}
+
+impl Account for Event<u32> {
+    fn type_id(&self) -> AccountType {
+        AccountType::Event
+    }
+    fn link_id(&self) -> Option<String> {
+        None
+    }
+    fn destination(&self) -> Option<String> {
+        self.destination.clone()
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn init(
+        &mut self,
+        linked_dates: Option<Dates>,
+        settings: &Settings,
+    ) -> Result<Vec<(u32, YearlyImpact)>, Box<dyn Error>> {
+        if linked_dates.is_some() {
+            return Err(String::from("Linked account dates provided but not used").into());
+        }
+        self.analysis = SingleTable::new(&self.table);
+        self.dates = Dates {
+            year_in: self.get_range_in(settings, linked_dates),
+            year_out: self.get_range_out(settings, linked_dates),
+        };
+        Ok(Vec::new())
+    }
+    fn get_value(&self, year: u32) -> Option<f64> {
+        self.analysis.value.get(year)
+    }
+    fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
+        match self.amount >= 0_f64 {
+            true => Some(self.event_range(settings, linked_dates)),
+            false => None,
+        }
+    }
+    fn get_range_out(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
+        match self.amount < 0_f64 {
+            true => Some(self.event_range(settings, linked_dates)),
+            false => None,
+        }
+    }
+    fn get_inputs(&self) -> String {
+        String::from("Hello")
+    }
+    fn plot_to_file(&self, filepath: String, width: u32, height: u32) {
+        scatter_plot_file(
+            filepath,
+            vec![("Amount".into(), &self.analysis.value)],
+            self.name(),
+            width,
+            height,
+        );
+    }
+    fn plot_to_buf(&self, width: u32, height: u32) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        scatter_plot_buf(
+            vec![("Amount".into(), &self.analysis.value)],
+            self.name(),
+            width,
+            height,
+        )
+    }
+    fn get_plot_data(&self) -> Vec<PlotDataSet> {
+        self.analysis.get_plot_data()
+    }
+    fn simulate(
+        &mut self,
+        year: u32,
+        _linked_value: Option<f64>,
+        _totals: &YearlyTotals,
+        _settings: &Settings,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        self.analysis.add_year(year, false)?;
+
+        let happens = self
+            .dates
+            .year_in
+            .or(self.dates.year_out)
+            .is_some_and(|range| range.contains(year));
+        if !happens {
+            return Ok(YearlyImpact::default());
+        }
+
+        // Add the event to value table
+        self.analysis.value.update(year, self.amount);
+
+        // Only the part of the money received above the cost basis is taxed
+        let taxed = (self.amount - self.cost_basis.unwrap_or_default()).max(0_f64);
+        // Money for an event with a destination account is moved by the simulation instead of
+        // passing through net
+        let cash = match self.destination {
+            Some(_) => 0_f64,
+            None => self.amount,
+        };
+
+        Ok(YearlyImpact {
+            expense: (-cash).max(0_f64),
+            healthcare_expense: 0_f64,
+            col: 0_f64,
+            saving: 0_f64,
+            income_taxable: match self.tax_treatment {
+                EventTax::Income => taxed,
+                _ => 0_f64,
+            },
+            capital_gains: match self.tax_treatment {
+                EventTax::CapitalGains => taxed,
+                _ => 0_f64,
+            },
+            income: cash.max(0_f64),
+            hsa: 0_f64,
+            penalty: 0_f64,
+        })
+    }
+    fn write(&self, filepath: String) {
+        self.analysis.write(filepath);
+    }
+}
+
+impl Event<u32> {
+    /// Single year range of the event
+    fn event_range(&self, settings: &Settings, linked_dates: Option<Dates>) -> YearRange {
+        let year = self
+            .year
+            .value(settings, linked_dates, YearEvalType::StartIn);
+        YearRange {
+            start: year,
+            end: year,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{Span, SsaSettings};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 100,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    fn test_event(amount: f64, tax_treatment: EventTax, cost_basis: Option<f64>) -> Event<u32> {
+        Event {
+            name: "Event".into(),
+            table: Table::default(),
+            year: YearInput::ConstantInt(2002),
+            amount,
+            tax_treatment,
+            cost_basis,
+            destination: None,
+            notes: None,
+            analysis: SingleTable::default(),
+            dates: Dates::default(),
+        }
+    }
+
+    fn simulate_years(account: &mut Event<u32>, settings: &Settings) -> Vec<YearlyImpact> {
+        account.init(None, settings).unwrap();
+        (2000..2005)
+            .map(|year| {
+                account
+                    .simulate(year, None, &YearlyTotals::new(), settings)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn event_impact() {
+        let settings = test_settings_values();
+
+        // a bonus is paid once and taxed as income
+        let impacts = simulate_years(
+            &mut test_event(5_000_f64, EventTax::Income, None),
+            &settings,
+        );
+        assert_approx_eq!(f64, impacts[1].income, 0_f64);
+        assert_approx_eq!(f64, impacts[2].income, 5_000_f64);
+        assert_approx_eq!(f64, impacts[2].income_taxable, 5_000_f64);
+        assert_approx_eq!(f64, impacts[3].income, 0_f64);
+
+        // only the gain on a home sale is taxed
+        let mut sale = test_event(300_000_f64, EventTax::CapitalGains, Some(200_000_f64));
+        let impacts = simulate_years(&mut sale, &settings);
+        assert_approx_eq!(f64, impacts[2].income, 300_000_f64);
+        assert_approx_eq!(f64, impacts[2].capital_gains, 100_000_f64);
+        assert_approx_eq!(f64, impacts[2].income_taxable, 0_f64);
+
+        // a purchase is an expense that is not taxed
+        let mut purchase = test_event(-20_000_f64, EventTax::Income, None);
+        let impacts = simulate_years(&mut purchase, &settings);
+        assert_approx_eq!(f64, impacts[2].expense, 20_000_f64);
+        assert_approx_eq!(f64, impacts[2].income, 0_f64);
+        assert_approx_eq!(f64, impacts[2].income_taxable, 0_f64);
+        assert_approx_eq!(f64, purchase.get_value(2002).unwrap(), -20_000_f64);
+        assert!(purchase.get_range_in(&settings, None).is_none());
+    }
+}
@@ -131,6 +131,7 @@ impl Account for Expense<u32> {
     fn simulate(
         &mut self,
         year: u32,
+        _linked_value: Option<f64>,
         _totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -153,8 +154,10 @@ impl Account for Expense<u32> {
                 col: result.expense,
                 saving: 0_f64,
                 income_taxable: 0_f64,
+                capital_gains: 0_f64,
                 income: 0_f64,
                 hsa: 0_f64,
+                penalty: 0_f64,
             }),
             false => Ok(YearlyImpact {
                 expense: result.expense,
@@ -162,8 +165,10 @@ impl Account for Expense<u32> {
                 col: result.expense,
                 saving: 0_f64,
                 income_taxable: 0_f64,
+                capital_gains: 0_f64,
                 income: 0_f64,
                 hsa: 0_f64,
+                penalty: 0_f64,
             }),
         }
     }
@@ -197,6 +202,15 @@ mod tests {
                     high: 80_f64,
                 },
             },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
         }
     }
 
@@ -219,7 +233,7 @@ mod tests {
         let settings = test_settings_values();
         account.init(None, &settings).unwrap();
         let year = 2010_u32;
-        let update = account.simulate(year, &yearly_totals, &settings).unwrap();
+        let update = account.simulate(year, None, &yearly_totals, &settings).unwrap();
 
         println!("{:?}", account.analysis.value.get(year));
         println!("{:?}", update);
@@ -41,6 +41,8 @@ pub struct Hsa<T: std::cmp::Ord> {
     withdrawal_value: f64,
     /// How cashflow in this account is treated for tax purposes
     tax_status: TaxStatus,
+    /// Person in the household the account belongs to (you when not set)
+    owner: Option<Owner>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -50,6 +52,12 @@ pub struct Hsa<T: std::cmp::Ord> {
     /// Calculated date values as a year based on input values
     #[serde(skip)]
     dates: Dates,
+    /// Percent interest earned in each year used instead of yearly_return (set for Monte Carlo runs)
+    #[serde(skip)]
+    return_sequence: Option<Table<u32>>,
+    /// Penalty for withdrawals made before the qualifying age
+    #[serde(skip)]
+    penalty: Option<Penalty>,
 }
 
 impl From<Hsa<String>> for Hsa<u32> {
@@ -66,11 +74,14 @@ impl From<Hsa<String>> for Hsa<u32> {
             employer_contribution: other.employer_contribution,
             yearly_return: other.yearly_return,
             tax_status: other.tax_status,
+            owner: other.owner,
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
             withdrawal_type: WithdrawalOptions::Other,
             withdrawal_value: 0_f64,
+            return_sequence: other.return_sequence,
+            penalty: other.penalty,
         }
     }
 }
@@ -82,6 +93,9 @@ impl Account for Hsa<u32> {
     fn link_id(&self) -> Option<String> {
         None
     }
+    fn owner(&self) -> Owner {
+        self.owner.unwrap_or_default()
+    }
     fn name(&self) -> String {
         self.name.clone()
     }
@@ -98,6 +112,10 @@ impl Account for Hsa<u32> {
             year_in: self.get_range_in(settings, linked_dates),
             year_out: self.get_range_out(settings, linked_dates),
         };
+        self.penalty = settings
+            .early_withdrawal_penalties
+            .as_ref()
+            .map(|penalties| penalties.hsa.resolve(settings.year_born_of(self.owner())));
         Ok(self
             .table
             .0
@@ -116,6 +134,9 @@ impl Account for Hsa<u32> {
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year)
+    }
     fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
         Some(YearRange {
             start: self
@@ -177,9 +198,23 @@ impl Account for Hsa<u32> {
     fn get_plot_data(&self) -> Vec<PlotDataSet> {
         self.analysis.get_plot_data()
     }
+    fn set_return_sequence(&mut self, returns: Table<u32>) {
+        self.return_sequence = Some(returns);
+    }
+    fn contribution_type(&self) -> Option<ContributionOptions> {
+        Some(self.contribution_type)
+    }
+    fn limit_kind(&self) -> Option<LimitKind> {
+        Some(LimitKind::Hsa)
+    }
+    fn limited_contribution(&self, year: u32) -> f64 {
+        // Employer contributions are included in the contributions table
+        self.analysis.contributions.get(year).unwrap_or_default()
+    }
     fn simulate(
         &mut self,
         year: u32,
+        _linked_value: Option<f64>,
         totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -192,7 +227,7 @@ impl Account for Hsa<u32> {
 
         // Calculate earnings
         result.earning =
-            self.analysis.value.get(year).unwrap() * (self.yearly_return.value(settings) / 100.0); // calculate earnings from interest
+            self.analysis.value.get(year).unwrap() * (self.get_yearly_return(year, settings) / 100.0); // calculate earnings from interest
 
         // Add earnings to earnings and value tables
         self.analysis.earnings.update(year, result.earning);
@@ -200,8 +235,14 @@ impl Account for Hsa<u32> {
 
         // Calculate contribution
         if self.dates.year_in.unwrap().contains(year) {
-            result.contribution = self.get_contribution(year, totals, settings);
             result.employer_contribution = fixed_with_inflation(self.employer_contribution, year, settings);
+            // Employer contributions count towards the limit
+            result.contribution = self.analysis.limit_contribution(
+                year,
+                self.get_contribution(year, totals, settings),
+                self.contribution_room(year, totals, settings)
+                    .map(|limit| limit - result.employer_contribution),
+            );
         }
 
         // Add contribution to contribution and value tables
@@ -238,9 +279,26 @@ impl Account for Hsa<u32> {
             col: 0_f64,
             saving: 0_f64,
             income_taxable: 0_f64,
+            capital_gains: 0_f64,
             income: 0_f64,
             hsa: result.contribution + result.employer_contribution + result.earning
                 - result.withdrawal,
+            penalty: 0_f64,
+        })
+    }
+    fn withdraw(&mut self, year: u32, amount: f64) -> Result<YearlyImpact, Box<dyn Error>> {
+        // Money that is not used for healthcare is taxed as income
+        let withdrawal = amount.min(self.analysis.value.get(year).unwrap_or_default()).max(0_f64);
+        self.analysis.withdrawals.update(year, withdrawal);
+        self.analysis.value.update(year, -withdrawal);
+        Ok(YearlyImpact {
+            hsa: -withdrawal,
+            income_taxable: withdrawal,
+            income: withdrawal,
+            penalty: self
+                .penalty
+                .map_or(0_f64, |penalty| penalty.value(year, withdrawal)),
+            ..Default::default()
         })
     }
     fn write(&self, filepath: String) {
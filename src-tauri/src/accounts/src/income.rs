@@ -25,6 +25,8 @@ pub struct Income<T: std::cmp::Ord> {
     end_in: YearInput,
     /// Yearly increase in income as a percent
     raise: PercentInput,
+    /// Person in the household the account belongs to (you when not set)
+    owner: Option<Owner>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -64,6 +66,7 @@ impl From<Income<String>> for Income<u32> {
             start_in: other.start_in,
             end_in: other.end_in,
             raise: other.raise,
+            owner: other.owner,
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
@@ -78,6 +81,9 @@ impl Account for Income<u32> {
     fn link_id(&self) -> Option<String> {
         None
     }
+    fn owner(&self) -> Owner {
+        self.owner.unwrap_or_default()
+    }
     fn name(&self) -> String {
         self.name.clone()
     }
@@ -149,6 +155,7 @@ impl Account for Income<u32> {
     fn simulate(
         &mut self,
         year: u32,
+        _linked_value: Option<f64>,
         _totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -156,10 +163,12 @@ impl Account for Income<u32> {
         let mut result = WorkingValues::default();
         self.analysis.add_year(year, false)?;
 
-        // Calculate earnings
-        if self.dates.year_in.unwrap().contains(year) {
-            let raise = self.raise.value(settings) / 100.0 + 1.0;
-            result.earning = self.base * f64::powf(raise, (year - start_in) as f64);
+        // Calculate earnings (the raise for each year is applied on top of the previous year)
+        // Nothing is earned after the owner dies
+        if self.dates.year_in.unwrap().contains(year) && settings.is_alive(self.owner(), year) {
+            result.earning = ((start_in + 1)..=year).fold(self.base, |earning, y| {
+                earning * (self.raise.value(y, self.owner(), settings) / 100.0 + 1.0)
+            });
         }
 
         // Add earnings to value tables
@@ -171,8 +180,10 @@ impl Account for Income<u32> {
             col: 0_f64,
             saving: 0_f64,
             income_taxable: result.earning,
+            capital_gains: 0_f64,
             income: result.earning,
             hsa: 0_f64,
+            penalty: 0_f64,
         })
     }
     fn write(&self, filepath: String) {
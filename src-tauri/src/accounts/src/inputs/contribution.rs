@@ -3,8 +3,7 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use super::PercentInput;
-// use super::fixed_with_inflation;
+use super::{fixed_with_inflation, Owner, PercentInput, Settings};
 
 /// description used to populate account dropdown for contribution type selection
 #[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
@@ -17,15 +16,241 @@ pub enum ContributionOptions {
     PercentOfIncome,
     /// fixed dollar amount compensated for inflation from year start (ie dollar amount is in current dollars)
     FixedWithInflation,
+    /// contribution limit for the account including catch-up contributions
+    MaxAllowed,
 }
 
-/// Employer matching for retirement accounts
+/// Kind of tax advantaged account that has a yearly contribution limit
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    /// Employer plan such as a 401k or 403b
+    Workplace,
+    /// Individual retirement account (traditional or Roth)
+    Ira,
+    /// Health savings account
+    Hsa,
+}
+
+/// Yearly contribution limit for one kind of account
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionLimit {
+    /// Most that can be contributed in a year [in today's dollars]
+    pub limit: f64,
+    /// Extra amount that can be contributed once you reach catch_up_age [in today's dollars]
+    pub catch_up: f64,
+    /// Age that catch-up contributions are allowed at (such as 50)
+    pub catch_up_age: u32,
+}
+
+impl ContributionLimit {
+    /// Calculate the limit for a year given the age of the account owner in that year
+    pub fn value(&self, year: u32, age: u32, settings: &Settings) -> f64 {
+        let catch_up = match age >= self.catch_up_age {
+            true => self.catch_up,
+            false => 0_f64,
+        };
+        fixed_with_inflation(self.limit + catch_up, year, settings)
+    }
+}
+
+/// Yearly contribution limits for each kind of tax advantaged account
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionLimits {
+    /// Limit for employer plans
+    pub workplace: ContributionLimit,
+    /// Limit for individual retirement accounts
+    pub ira: ContributionLimit,
+    /// Limit for health savings accounts
+    pub hsa: ContributionLimit,
+}
+
+impl ContributionLimits {
+    /// Limit for a kind of account
+    pub fn get(&self, kind: LimitKind) -> &ContributionLimit {
+        match kind {
+            LimitKind::Workplace => &self.workplace,
+            LimitKind::Ira => &self.ira,
+            LimitKind::Hsa => &self.hsa,
+        }
+    }
+}
+
+/// One level of employer matching for retirement accounts
 #[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[ts(export)]
 #[serde(rename_all = "snake_case")]
-pub struct EmployerMatch {
+pub struct MatchTier {
     /// Percentage that the employer will match (such as 50%)
     pub amount: PercentInput,
     /// Limit of employer's match (such as up to the first 6% that the employee saves)
     pub limit: PercentInput,
-}
\ No newline at end of file
+}
+
+/// Employer matching for retirement accounts
+///
+/// Matching is made up of one or more tiers.  Each tier matches the portion of the employee
+/// contribution that falls within its limit, starting where the previous tier's limit ended.
+/// For example 100% of the first 3% of income and 50% of the next 2% of income.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum EmployerMatch {
+    /// Multiple levels of matching applied in order
+    Tiered(Vec<MatchTier>),
+    /// Single level of matching
+    Single(MatchTier),
+}
+
+impl EmployerMatch {
+    /// Return the matching tiers in the order they are applied
+    pub fn tiers(&self) -> Vec<&MatchTier> {
+        match self {
+            Self::Tiered(tiers) => tiers.iter().collect(),
+            Self::Single(tier) => vec![tier],
+        }
+    }
+    /// Calculate the employer contribution for an employee contribution and the linked income
+    pub fn value(
+        &self,
+        contribution: f64,
+        income: f64,
+        year: u32,
+        owner: Owner,
+        settings: &Settings,
+    ) -> f64 {
+        let mut remaining_contribution = contribution;
+        let mut employer_contribution = 0_f64;
+        for tier in self.tiers() {
+            if remaining_contribution <= 0_f64 {
+                break;
+            }
+            // portion of the contribution that falls within the limit of this tier
+            let limit = income * tier.limit.value(year, owner, settings) / 100_f64;
+            let tier_contribution = remaining_contribution.min(limit);
+            employer_contribution +=
+                tier_contribution * tier.amount.value(year, owner, settings) / 100_f64;
+            remaining_contribution -= tier_contribution;
+        }
+        employer_contribution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{Span, SsaSettings};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 100,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    #[test]
+    fn employer_match_single() {
+        let settings = test_settings_values();
+        let matching = EmployerMatch::Single(MatchTier {
+            amount: PercentInput::ConstantFloat(50_f64),
+            limit: PercentInput::ConstantFloat(6_f64),
+        });
+        // below the limit the whole contribution is matched
+        assert_approx_eq!(
+            f64,
+            matching.value(3_000_f64, 100_000_f64, 2000, Owner::Primary, &settings),
+            1_500_f64
+        );
+        // above the limit only the first 6% of income is matched
+        assert_approx_eq!(
+            f64,
+            matching.value(10_000_f64, 100_000_f64, 2000, Owner::Primary, &settings),
+            3_000_f64
+        );
+    }
+
+    #[test]
+    fn employer_match_tiered() {
+        let settings = test_settings_values();
+        let matching = EmployerMatch::Tiered(vec![
+            MatchTier {
+                amount: PercentInput::ConstantFloat(100_f64),
+                limit: PercentInput::ConstantFloat(3_f64),
+            },
+            MatchTier {
+                amount: PercentInput::ConstantFloat(50_f64),
+                limit: PercentInput::ConstantFloat(2_f64),
+            },
+        ]);
+        assert_approx_eq!(
+            f64,
+            matching.value(2_000_f64, 100_000_f64, 2000, Owner::Primary, &settings),
+            2_000_f64
+        );
+        assert_approx_eq!(
+            f64,
+            matching.value(4_000_f64, 100_000_f64, 2000, Owner::Primary, &settings),
+            3_500_f64
+        );
+        assert_approx_eq!(
+            f64,
+            matching.value(10_000_f64, 100_000_f64, 2000, Owner::Primary, &settings),
+            4_000_f64
+        );
+    }
+
+    #[test]
+    fn employer_match_deserialize() {
+        let single: EmployerMatch = serde_json::from_str(r#"{"amount": 50, "limit": 6}"#).unwrap();
+        assert_eq!(single.tiers().len(), 1);
+        let tiered: EmployerMatch =
+            serde_json::from_str(r#"[{"amount": 100, "limit": 3}, {"amount": 50, "limit": 2}]"#)
+                .unwrap();
+        assert_eq!(tiered.tiers().len(), 2);
+    }
+
+    #[test]
+    fn contribution_limit() {
+        let settings = test_settings_values();
+        let limit = ContributionLimit {
+            limit: 20_000_f64,
+            catch_up: 5_000_f64,
+            catch_up_age: 50,
+        };
+        assert_approx_eq!(f64, limit.value(2000, 49, &settings), 20_000_f64);
+        assert_approx_eq!(f64, limit.value(2000, 50, &settings), 25_000_f64);
+        // limits are indexed to inflation
+        assert_approx_eq!(f64, limit.value(2001, 40, &settings), 21_000_f64);
+    }
+}
@@ -0,0 +1,179 @@
+//! User input Roth conversion plan
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{fixed_with_inflation, Settings, YearEvalType, YearInput};
+use crate::simulation::YearRange;
+
+/// used to populate account dropdown for Roth conversion type selection
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionOptions {
+    /// Convert the same amount every year
+    Fixed,
+    /// Convert an amount in today's dollars that increases with inflation
+    FixedWithInflation,
+    /// Convert enough to fill taxable income up to the top of a tax bracket
+    FillBracket,
+}
+
+/// Yearly move of money from a pretax retirement account to a Roth account
+///
+/// The amount converted is taxed as income in the year it is moved but stays in savings.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RothConversion {
+    /// Pretax retirement account (uuid) the money is taken from
+    pub from_account: String,
+    /// Roth retirement account (uuid) the money is moved into
+    pub to_account: String,
+    /// Calendar year of the first conversion
+    pub start_year: YearInput,
+    /// Calendar year of the last conversion
+    pub end_year: YearInput,
+    /// Determines how to interpret the value in conversion_value
+    pub conversion_type: ConversionOptions,
+    /// Amount to convert each year or the rate (percent) of the bracket to fill
+    pub conversion_value: f64,
+}
+
+impl RothConversion {
+    /// Years that conversions are made in
+    pub fn years(&self, settings: &Settings) -> YearRange {
+        YearRange {
+            start: self
+                .start_year
+                .value(settings, None, YearEvalType::StartOut),
+            end: self.end_year.value(settings, None, YearEvalType::EndOut),
+        }
+    }
+    /// Amount to convert in a year given the taxable income already earned that year
+    pub fn amount(
+        &self,
+        year: u32,
+        taxable_income: f64,
+        settings: &Settings,
+    ) -> Result<f64, String> {
+        match self.conversion_type {
+            ConversionOptions::Fixed => Ok(self.conversion_value),
+            ConversionOptions::FixedWithInflation => {
+                Ok(fixed_with_inflation(self.conversion_value, year, settings))
+            }
+            ConversionOptions::FillBracket => {
+                let brackets = settings
+                    .tax_brackets
+                    .as_ref()
+                    .ok_or_else(|| String::from("Filling a tax bracket requires tax brackets"))?;
+                let limit = brackets
+                    .bracket_limit(self.conversion_value, year, settings)
+                    .ok_or_else(|| format!("There is no {}% tax bracket", self.conversion_value))?;
+                Ok((limit - taxable_income).max(0_f64))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{Span, SsaSettings, TaxBracket, TaxBrackets};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 100,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: Some(TaxBrackets {
+                brackets: vec![
+                    TaxBracket {
+                        threshold: 0_f64,
+                        rate: 10_f64,
+                    },
+                    TaxBracket {
+                        threshold: 10_000_f64,
+                        rate: 20_f64,
+                    },
+                ],
+                standard_deduction: 5_000_f64,
+            }),
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    fn test_conversion(
+        conversion_type: ConversionOptions,
+        conversion_value: f64,
+    ) -> RothConversion {
+        RothConversion {
+            from_account: "ira".into(),
+            to_account: "roth".into(),
+            start_year: YearInput::ConstantInt(2000),
+            end_year: YearInput::ConstantInt(2010),
+            conversion_type,
+            conversion_value,
+        }
+    }
+
+    #[test]
+    fn conversion_amount() {
+        let mut settings = test_settings_values();
+
+        let conversion = test_conversion(ConversionOptions::Fixed, 1_000_f64);
+        assert_approx_eq!(
+            f64,
+            conversion.amount(2001, 0_f64, &settings).unwrap(),
+            1_000_f64
+        );
+        let conversion = test_conversion(ConversionOptions::FixedWithInflation, 1_000_f64);
+        assert_approx_eq!(
+            f64,
+            conversion.amount(2001, 0_f64, &settings).unwrap(),
+            1_050_f64
+        );
+
+        // the 10% bracket ends at 15,000 of taxable income (including the deduction)
+        let conversion = test_conversion(ConversionOptions::FillBracket, 10_f64);
+        assert_approx_eq!(
+            f64,
+            conversion.amount(2000, 12_000_f64, &settings).unwrap(),
+            3_000_f64
+        );
+        assert_approx_eq!(
+            f64,
+            conversion.amount(2000, 20_000_f64, &settings).unwrap(),
+            0_f64
+        );
+        let conversion = test_conversion(ConversionOptions::FillBracket, 12_f64);
+        assert!(conversion.amount(2000, 0_f64, &settings).is_err());
+
+        settings.tax_brackets = None;
+        let conversion = test_conversion(ConversionOptions::FillBracket, 10_f64);
+        assert!(conversion.amount(2000, 0_f64, &settings).is_err());
+    }
+}
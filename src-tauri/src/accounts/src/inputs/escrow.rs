@@ -0,0 +1,120 @@
+//! User input mortgage escrow
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{Owner, PercentInput, Settings};
+
+/// Property tax and homeowner insurance paid through a mortgage
+///
+/// Both are still paid after the mortgage is paid off because the house is still owned.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Escrow {
+    /// Assessed value of the home in the first simulated year
+    pub assessed_value: f64,
+    /// Yearly increase in the assessed value as a percent
+    pub assessment_growth: PercentInput,
+    /// Property tax as a percent of the assessed value
+    pub property_tax_rate: f64,
+    /// Homeowner insurance premium in the first simulated year
+    pub insurance: f64,
+    /// Yearly increase in the insurance premium as a percent
+    pub insurance_inflation: PercentInput,
+}
+
+impl Escrow {
+    /// Property tax for the year
+    pub fn property_tax(&self, year: u32, owner: Owner, settings: &Settings) -> f64 {
+        grow(self.assessed_value, &self.assessment_growth, year, owner, settings)
+            * self.property_tax_rate
+            / 100_f64
+    }
+    /// Homeowner insurance premium for the year
+    pub fn insurance(&self, year: u32, owner: Owner, settings: &Settings) -> f64 {
+        grow(self.insurance, &self.insurance_inflation, year, owner, settings)
+    }
+    /// Total paid into escrow for the year
+    pub fn value(&self, year: u32, owner: Owner, settings: &Settings) -> f64 {
+        self.property_tax(year, owner, settings) + self.insurance(year, owner, settings)
+    }
+}
+
+/// Apply the growth of each year since the start of the simulation to `value`
+fn grow(value: f64, growth: &PercentInput, year: u32, owner: Owner, settings: &Settings) -> f64 {
+    ((settings.year_start + 1)..=year).fold(value, |value, y| {
+        value * (1_f64 + growth.value(y, owner, settings) / 100_f64)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{PercentSuggestions, Span, SsaSettings};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 100,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    #[test]
+    fn escrow_growth() {
+        let settings = test_settings_values();
+        let escrow = Escrow {
+            assessed_value: 200_000_f64,
+            assessment_growth: PercentInput::ConstantFloat(2_f64),
+            property_tax_rate: 1_f64,
+            insurance: 1_000_f64,
+            insurance_inflation: PercentInput::Calculate(PercentSuggestions::InflationBase),
+        };
+        assert_approx_eq!(f64, escrow.property_tax(2000, Owner::Primary, &settings), 2_000_f64);
+        assert_approx_eq!(f64, escrow.insurance(2000, Owner::Primary, &settings), 1_000_f64);
+        assert_approx_eq!(
+            f64,
+            escrow.property_tax(2002, Owner::Primary, &settings),
+            2_080.8,
+            epsilon = 1e-9
+        );
+        assert_approx_eq!(
+            f64,
+            escrow.insurance(2002, Owner::Primary, &settings),
+            1_102.5,
+            epsilon = 1e-9
+        );
+        assert_approx_eq!(
+            f64,
+            escrow.value(2002, Owner::Primary, &settings),
+            3_183.3,
+            epsilon = 1e-9
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! User input one time event values
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// used to populate account dropdown for event tax treatment selection
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTax {
+    /// Not taxed (such as an inheritance or a large purchase)
+    Untaxed,
+    /// Taxed as income (such as a bonus)
+    Income,
+    /// Taxed at the capital gains rate (such as the gain on a home sale)
+    CapitalGains,
+}
@@ -0,0 +1,181 @@
+//! User input changes to the rate or term of a loan
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{Owner, PercentInput, Settings, YearEvalType, YearInput};
+use crate::simulation::Table;
+
+/// Limits on how far an adjustable rate can move
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct RateCaps {
+    /// Largest change in the rate from one year to the next (percent)
+    pub adjustment: f64,
+    /// Highest rate allowed over the life of the loan (percent)
+    pub lifetime: f64,
+}
+
+/// Dated change to a loan such as a refinance or the reset of an adjustable rate
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanChange {
+    /// Calendar year the change takes effect
+    pub year: YearInput,
+    /// Interest rate (APR) from this year on.  Leave empty to keep the current rate.
+    pub rate: Option<PercentInput>,
+    /// Years left on the loan after the change.  Only used with amortized payments.
+    pub term: Option<u32>,
+    /// Limits on how far the rate can move each year from this year on (adjustable rates)
+    pub caps: Option<RateCaps>,
+    /// One time cost of the change such as refinance closing costs
+    pub closing_costs: f64,
+}
+
+impl LoanChange {
+    /// Calendar year the change takes effect
+    pub fn year(&self, settings: &Settings) -> u32 {
+        self.year.value(settings, None, YearEvalType::StartOut)
+    }
+}
+
+/// Interest rate (APR) of a loan in every simulated year after applying its changes
+///
+/// A change to the rate stays in effect until a later change replaces it.  While a change
+/// with caps is in effect the rate can not move more than the adjustment cap from the rate of
+/// the year before or go above the lifetime cap.
+pub fn loan_rates(
+    rate: &PercentInput,
+    changes: &[LoanChange],
+    owner: Owner,
+    settings: &Settings,
+) -> Table<u32> {
+    let mut changes: Vec<(u32, &LoanChange)> = changes
+        .iter()
+        .filter(|change| change.rate.is_some())
+        .map(|change| (change.year(settings), change))
+        .collect();
+    changes.sort_by_key(|(year, _change)| *year);
+
+    let mut rates = Table::default();
+    let mut previous: Option<f64> = None;
+    for year in settings.year_start()..settings.year_end() {
+        let active = changes.iter().rev().find(|(start, _change)| *start <= year);
+        let value = match active {
+            Some((_start, change)) => {
+                let value = change.rate.as_ref().unwrap().value(year, owner, settings);
+                match (change.caps, previous) {
+                    (Some(caps), Some(previous)) => value
+                        .clamp(previous - caps.adjustment, previous + caps.adjustment)
+                        .min(caps.lifetime),
+                    (Some(caps), None) => value.min(caps.lifetime),
+                    (None, _) => value,
+                }
+            }
+            None => rate.value(year, owner, settings),
+        };
+        rates.insert(year, value);
+        previous = Some(value);
+    }
+    rates
+}
+
+/// Years left on the loan after the changes that take effect in `year` (if any change the term)
+pub fn loan_term(changes: &[LoanChange], year: u32, settings: &Settings) -> Option<u32> {
+    changes
+        .iter()
+        .rev()
+        .filter(|change| change.year(settings) == year)
+        .find_map(|change| change.term)
+}
+
+/// Closing costs of the changes that take effect in `year`
+pub fn closing_costs(changes: &[LoanChange], year: u32, settings: &Settings) -> f64 {
+    changes
+        .iter()
+        .filter(|change| change.year(settings) == year)
+        .map(|change| change.closing_costs)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{Span, SsaSettings};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 30,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    #[test]
+    fn rates_and_terms() {
+        let settings = test_settings_values();
+        let changes = vec![
+            // refinance
+            LoanChange {
+                year: YearInput::ConstantInt(2002),
+                rate: Some(PercentInput::ConstantFloat(4_f64)),
+                term: Some(30),
+                caps: None,
+                closing_costs: 6_000_f64,
+            },
+            // adjustable rate reset that is limited by its caps
+            LoanChange {
+                year: YearInput::ConstantInt(2005),
+                rate: Some(PercentInput::ConstantFloat(9_f64)),
+                term: None,
+                caps: Some(RateCaps {
+                    adjustment: 2_f64,
+                    lifetime: 7_f64,
+                }),
+                closing_costs: 0_f64,
+            },
+        ];
+        let rates = loan_rates(
+            &PercentInput::ConstantFloat(6_f64),
+            &changes,
+            Owner::Primary,
+            &settings,
+        );
+        assert_approx_eq!(f64, rates.get(2001).unwrap(), 6_f64);
+        assert_approx_eq!(f64, rates.get(2002).unwrap(), 4_f64);
+        assert_approx_eq!(f64, rates.get(2005).unwrap(), 6_f64);
+        assert_approx_eq!(f64, rates.get(2006).unwrap(), 7_f64);
+        assert_approx_eq!(f64, rates.get(2009).unwrap(), 7_f64);
+
+        assert_eq!(loan_term(&changes, 2002, &settings), Some(30));
+        assert_eq!(loan_term(&changes, 2005, &settings), None);
+        assert_approx_eq!(f64, closing_costs(&changes, 2002, &settings), 6_000_f64);
+        assert_approx_eq!(f64, closing_costs(&changes, 2003, &settings), 0_f64);
+    }
+}
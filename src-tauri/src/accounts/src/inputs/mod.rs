@@ -9,18 +9,34 @@ use super::{Account, AccountWrapper};
 
 
 mod contribution;
+mod conversion;
+mod escrow;
+mod event;
 mod expense;
+mod loan_change;
+mod monte_carlo;
 mod payment;
+mod penalty;
 mod percent;
 mod settings;
+mod sweep;
+mod tax;
 mod withdrawal;
 mod year;
 
 pub use contribution::*;
+pub use conversion::*;
+pub use escrow::*;
+pub use event::*;
 pub use expense::*;
+pub use loan_change::*;
+pub use monte_carlo::*;
 pub use payment::*;
+pub use penalty::*;
 pub use percent::*;
 pub use settings::*;
+pub use sweep::*;
+pub use tax::*;
 pub use withdrawal::*;
 pub use year::*;
 
@@ -84,5 +100,11 @@ impl UserData<Box<dyn Account>> {
 
 
 pub fn fixed_with_inflation(initial_value: f64, year: u32, settings: &Settings) -> f64 {
-    initial_value * f64::powf(1_f64 + settings.inflation_base / 100_f64, (year - settings.year_start) as f64)
+    match (&settings.inflation_sequence, &settings.inflation_schedule) {
+        (None, None) => initial_value * f64::powf(1_f64 + settings.inflation_base / 100_f64, (year - settings.year_start) as f64),
+        // compound the inflation rate of each year since the start of the simulation
+        _ => (settings.year_start..year).fold(initial_value, |value, y| {
+            value * (1_f64 + settings.inflation_rate(y) / 100_f64)
+        }),
+    }
 }
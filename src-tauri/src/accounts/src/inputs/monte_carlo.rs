@@ -0,0 +1,41 @@
+//! User input Monte Carlo options
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+/// Normal distribution of yearly returns
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct ReturnDistribution {
+    /// Average yearly return (percent)
+    pub mean: f64,
+    /// Standard deviation of the yearly return (percent)
+    pub volatility: f64,
+}
+
+/// Where an account gets its random returns from
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum ReturnModel {
+    /// Name of an asset class (every account in an asset class gets the same return each year)
+    AssetClass(String),
+    /// Distribution used only by this account
+    Distribution(ReturnDistribution),
+}
+
+/// Options for running a plan many times with random returns
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloOptions {
+    /// Number of times to run the plan
+    pub runs: u32,
+    /// Seed for the random number generator (the same seed gives the same results)
+    pub seed: u32,
+    /// Return distributions keyed by asset class name
+    pub asset_classes: HashMap<String, ReturnDistribution>,
+    /// How each account (keyed by uuid) gets its returns.  Accounts that are not listed use their yearly return input.
+    pub accounts: HashMap<String, ReturnModel>,
+}
@@ -14,6 +14,19 @@ pub enum PaymentOptions {
     Fixed,
     /// fixed dollar amount compensated for inflation from year start (ie dollar amount is in current dollars)
     FixedWithInflation,
+    /// level payment that pays off the balance over the years left on the loan (the term sets the number of years left)
+    Amortized,
+}
+
+/// Month by month payment schedule for a mortgage
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Amortization {
+    /// Months left on the loan at the start of the simulation (such as 360 for a new 30 year mortgage)
+    pub term: u32,
+    /// Extra amount paid toward the principal every month
+    pub extra_principal: f64,
 }
 
 // impl PaymentOptions {
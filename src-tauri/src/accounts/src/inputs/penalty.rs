@@ -0,0 +1,63 @@
+//! User input early withdrawal penalties
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::simulation::Penalty;
+
+/// Penalty for taking money out of an account before the qualifying age
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct EarlyWithdrawalPenalty {
+    /// Age withdrawals stop being penalized at (such as 59.5).  Ages are counted in whole calendar years so
+    /// an age of 59.5 penalizes withdrawals through the year you turn 59.
+    pub age: f64,
+    /// Penalty as a percent of the amount withdrawn
+    pub rate: f64,
+}
+
+impl EarlyWithdrawalPenalty {
+    /// Resolve the penalty to the calendar years it applies in for someone born in `year_born`
+    pub fn resolve(&self, year_born: u32) -> Penalty {
+        Penalty {
+            last_year: (year_born + self.age.ceil() as u32).saturating_sub(1),
+            rate: self.rate,
+        }
+    }
+}
+
+/// Early withdrawal penalties for each kind of account
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct EarlyWithdrawalPenalties {
+    /// Penalty for retirement accounts
+    pub retirement: EarlyWithdrawalPenalty,
+    /// Penalty for health savings accounts (only withdrawals that are not used for healthcare)
+    pub hsa: EarlyWithdrawalPenalty,
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+
+    #[test]
+    fn penalty_years() {
+        let penalty = EarlyWithdrawalPenalty {
+            age: 59.5,
+            rate: 10_f64,
+        }
+        .resolve(1960);
+        // penalized through the year you turn 59
+        assert_eq!(penalty.last_year, 2019);
+        assert_approx_eq!(f64, penalty.value(2019, 1_000_f64), 100_f64);
+        assert_approx_eq!(f64, penalty.value(2020, 1_000_f64), 0_f64);
+
+        let penalty = EarlyWithdrawalPenalty {
+            age: 65_f64,
+            rate: 20_f64,
+        }
+        .resolve(1960);
+        assert_eq!(penalty.last_year, 2024);
+    }
+}
@@ -2,11 +2,13 @@
 //!
 //! Some fields are allowed to be input as a percent.  These types parse those
 //! fields to return meaningful values based on text, calculate, and contant value inputs.
+//! Percents that change over time (such as returns that drop after retirement) can be input
+//! as a schedule which is evaluated for each year of the simulation.
 
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use super::settings;
+use super::settings::{self, Owner};
 
 /// These values can be input as constants or as computed values (strings)
 #[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -19,14 +21,83 @@ pub enum PercentInput {
     ConstantFloat(f64),
     /// Constant string
     ConstantString(String),
+    /// Value that changes by year or age
+    Schedule(RateSchedule),
 }
 
 impl PercentInput {
-    pub fn value(&self, settings: &settings::Settings) -> f64 {
+    /// Percent in effect for the year (ages in a schedule are those of `owner`)
+    pub fn value(&self, year: u32, owner: Owner, settings: &settings::Settings) -> f64 {
         match self {
-            Self::Calculate(input) => input.value(settings),
+            Self::Calculate(input) => input.value(year, settings),
             Self::ConstantFloat(input) => *input,
             Self::ConstantString(input) => input.parse().unwrap_or_default(),
+            Self::Schedule(input) => input.value(year, owner, settings),
+        }
+    }
+}
+
+/// What the steps of a rate schedule are keyed on
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleKey {
+    /// Calendar year
+    Year,
+    /// Age of the account owner
+    Age,
+}
+
+/// Percent that takes effect at a year or age
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct ScheduleStep {
+    /// Year or age the percent takes effect
+    pub at: u32,
+    /// Percent value
+    pub rate: f64,
+}
+
+/// Percent that changes over time
+///
+/// Before the first step the first rate is used and after the last step the last rate is used.
+/// In between, the rate steps to the value of the most recent step or, when interpolated,
+/// changes linearly from one step to the next.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct RateSchedule {
+    /// Whether steps are keyed on year or age
+    pub key: ScheduleKey,
+    /// Steps of the schedule
+    pub steps: Vec<ScheduleStep>,
+    /// Linearly interpolate between steps instead of stepping
+    pub interpolate: bool,
+}
+
+impl RateSchedule {
+    pub fn value(&self, year: u32, owner: Owner, settings: &settings::Settings) -> f64 {
+        let at = match self.key {
+            ScheduleKey::Year => year as f64,
+            ScheduleKey::Age => year as f64 - settings.year_born_of(owner) as f64,
+        };
+        let mut steps = self.steps.clone();
+        steps.sort_by_key(|step| step.at);
+
+        let next = steps.iter().position(|step| step.at as f64 > at);
+        match next {
+            None => steps.last().map_or(0_f64, |step| step.rate),
+            Some(0) => steps[0].rate,
+            Some(idx) => {
+                let (low, high) = (steps[idx - 1], steps[idx]);
+                match self.interpolate {
+                    true => {
+                        low.rate
+                            + (high.rate - low.rate) * (at - low.at as f64)
+                                / (high.at - low.at) as f64
+                    }
+                    false => low.rate,
+                }
+            }
         }
     }
 }
@@ -47,9 +118,9 @@ pub enum PercentSuggestions {
 }
 
 impl PercentSuggestions {
-    pub fn value(&self, settings: &settings::Settings) -> f64 {
+    pub fn value(&self, year: u32, settings: &settings::Settings) -> f64 {
         match self {
-            Self::InflationBase => settings.inflation_base,
+            Self::InflationBase => settings.inflation_rate(year),
         }
     }
 }
@@ -79,6 +150,15 @@ mod tests {
                     high: 80_f64,
                 },
             },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
         }
     }
 
@@ -88,8 +168,60 @@ mod tests {
         let p2 = PercentInput::ConstantFloat(75_f64);
         let p3 = PercentInput::ConstantString("40".into());
 
-        assert_approx_eq!(f64, p1.value(&test_settings_values()), 5_f64);
-        assert_approx_eq!(f64, p2.value(&test_settings_values()), 75_f64);
-        assert_approx_eq!(f64, p3.value(&test_settings_values()), 40_f64);
+        assert_approx_eq!(f64, p1.value(2000, Owner::Primary, &test_settings_values()), 5_f64);
+        assert_approx_eq!(f64, p2.value(2000, Owner::Primary, &test_settings_values()), 75_f64);
+        assert_approx_eq!(f64, p3.value(2000, Owner::Primary, &test_settings_values()), 40_f64);
+    }
+
+    #[test]
+    fn schedule() {
+        let settings = test_settings_values();
+        let mut schedule = RateSchedule {
+            key: ScheduleKey::Year,
+            steps: vec![
+                ScheduleStep { at: 2030, rate: 4_f64 },
+                ScheduleStep { at: 2010, rate: 8_f64 },
+            ],
+            interpolate: false,
+        };
+        let p1 = PercentInput::Schedule(schedule.clone());
+        assert_approx_eq!(f64, p1.value(2000, Owner::Primary, &settings), 8_f64);
+        assert_approx_eq!(f64, p1.value(2020, Owner::Primary, &settings), 8_f64);
+        assert_approx_eq!(f64, p1.value(2030, Owner::Primary, &settings), 4_f64);
+        assert_approx_eq!(f64, p1.value(2050, Owner::Primary, &settings), 4_f64);
+
+        schedule.interpolate = true;
+        assert_approx_eq!(f64, schedule.value(2020, Owner::Primary, &settings), 6_f64);
+        assert_approx_eq!(f64, schedule.value(2025, Owner::Primary, &settings), 5_f64);
+
+        // ages are relative to the year you were born
+        schedule.key = ScheduleKey::Age;
+        schedule.steps = vec![
+            ScheduleStep { at: 30, rate: 8_f64 },
+            ScheduleStep { at: 50, rate: 4_f64 },
+        ];
+        assert_approx_eq!(f64, schedule.value(2000, Owner::Primary, &settings), 8_f64);
+        assert_approx_eq!(f64, schedule.value(2020, Owner::Primary, &settings), 6_f64);
+
+        // and to the year the spouse was born for accounts they own
+        let mut settings = settings;
+        settings.spouse = Some(settings::Spouse {
+            age_retire: 60,
+            age_die: 90,
+            year_born: 1990,
+        });
+        assert_approx_eq!(f64, schedule.value(2020, Owner::Spouse, &settings), 8_f64);
+        assert_approx_eq!(f64, schedule.value(2030, Owner::Spouse, &settings), 6_f64);
+    }
+
+    #[test]
+    fn schedule_input() {
+        let p1: PercentInput = serde_json::from_str(
+            r#"{"key": "age", "steps": [{"at": 60, "rate": 7}, {"at": 70, "rate": 4}], "interpolate": true}"#,
+        )
+        .unwrap();
+        assert!(matches!(p1, PercentInput::Schedule(_)));
+        let p2: PercentInput = serde_json::from_str(r#""inflationBase""#).unwrap();
+        assert_eq!(p2, PercentInput::Calculate(PercentSuggestions::InflationBase));
     }
 }
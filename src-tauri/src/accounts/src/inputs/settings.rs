@@ -3,6 +3,12 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use super::{
+    CashSweep, ContributionLimits, DrawdownStrategy, EarlyWithdrawalPenalties, IncomeTax, LimitKind,
+    RateSchedule, RothConversion, TaxBrackets,
+};
+use crate::simulation::Table;
+
 /// Generic span (something that has a min and max value)
 #[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[ts(export)]
@@ -18,12 +24,47 @@ pub struct Span<T> {
 #[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct SsaSettings {
-    /// SSA breakpoints to interpolate between
+    /// SSA breakpoints to interpolate between.  These are compared to combined income (half of the benefit plus all other income)
     pub breakpoints: Span<f64>,
-    /// taxable_income_percentage
+    /// Percent of the benefit that is taxable at the low and high breakpoints
     pub taxable_income_percentage: Span<f64>,
 }
 
+/// Second person in the household
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Spouse {
+    /// Age your spouse plans to retire at
+    pub age_retire: u32,
+    /// How long your spouse plans to live
+    pub age_die: u32,
+    /// Year your spouse was born in
+    pub year_born: u32,
+}
+
+/// Person in the household an account belongs to
+#[derive(TS, Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Owner {
+    /// You (the person described by the age and year settings)
+    #[default]
+    Primary,
+    /// Your spouse
+    Spouse,
+}
+
+impl Owner {
+    /// The other person in the household
+    pub fn other(&self) -> Owner {
+        match self {
+            Owner::Primary => Owner::Spouse,
+            Owner::Spouse => Owner::Primary,
+        }
+    }
+}
+
 /// Analysis user settings
 #[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[ts(export)]
@@ -47,6 +88,25 @@ pub struct Settings {
     pub retirement_cost_of_living: f64,
     /// Social Security settings
     pub ssa: SsaSettings,
+    /// Second person in the household (when not set the household is just you)
+    pub spouse: Option<Spouse>,
+    /// Progressive income tax brackets (when not set all taxable income is taxed at tax_income)
+    pub tax_brackets: Option<TaxBrackets>,
+    /// End of year policy for investing surplus cash and covering deficits (when not set net is left alone)
+    pub cash_sweep: Option<CashSweep>,
+    /// How the cost of living is split across accounts that use planned withdrawals
+    pub drawdown: Option<DrawdownStrategy>,
+    /// Yearly move of money from a pretax retirement account to a Roth account
+    pub roth_conversion: Option<RothConversion>,
+    /// Yearly contribution limits for tax advantaged accounts (when not set contributions are not limited)
+    pub contribution_limits: Option<ContributionLimits>,
+    /// Penalties for withdrawing from tax advantaged accounts early (when not set there are no penalties)
+    pub early_withdrawal_penalties: Option<EarlyWithdrawalPenalties>,
+    /// Rate of inflation that changes over time used instead of inflation_base (ages are yours)
+    pub inflation_schedule: Option<RateSchedule>,
+    /// Rate of inflation for each year used instead of inflation_base (set for backtesting runs)
+    #[serde(skip)]
+    pub inflation_sequence: Option<Table<u32>>,
 }
 
 impl Settings {
@@ -84,17 +144,90 @@ impl Settings {
     pub fn year_die(&self) -> u32 {
         self.year_born + self.age_die
     }
+    /// Simulation runs until everyone in the household has died
     pub fn year_end(&self) -> u32 {
-        self.year_born + self.age_die
+        match &self.spouse {
+            Some(spouse) => self.year_die().max(spouse.year_born + spouse.age_die),
+            None => self.year_die(),
+        }
     }
     pub fn is_retired(&self, year: u32) -> bool {
         year >= self.year_retire()
     }
+    /// Year the owner was born in (your values are used when there is no spouse)
+    pub fn year_born_of(&self, owner: Owner) -> u32 {
+        match (owner, &self.spouse) {
+            (Owner::Spouse, Some(spouse)) => spouse.year_born,
+            _ => self.year_born,
+        }
+    }
+    /// Year the owner plans to retire (your values are used when there is no spouse)
+    pub fn year_retire_of(&self, owner: Owner) -> u32 {
+        match (owner, &self.spouse) {
+            (Owner::Spouse, Some(spouse)) => spouse.year_born + spouse.age_retire,
+            _ => self.year_retire(),
+        }
+    }
+    /// Year the owner plans to die (your values are used when there is no spouse)
+    pub fn year_die_of(&self, owner: Owner) -> u32 {
+        match (owner, &self.spouse) {
+            (Owner::Spouse, Some(spouse)) => spouse.year_born + spouse.age_die,
+            _ => self.year_die(),
+        }
+    }
+    /// Owner is still alive in the specified year
+    pub fn is_alive(&self, owner: Owner, year: u32) -> bool {
+        match (owner, &self.spouse) {
+            (Owner::Spouse, None) => false,
+            _ => year < self.year_die_of(owner),
+        }
+    }
+    /// Owner is alive but their spouse has died in the specified year
+    pub fn is_survivor(&self, owner: Owner, year: u32) -> bool {
+        self.spouse.is_some() && self.is_alive(owner, year) && !self.is_alive(owner.other(), year)
+    }
+    /// Contribution limit in a year for a kind of account that belongs to `owner` (None when limits are not set)
+    pub fn contribution_limit(&self, kind: LimitKind, owner: Owner, year: u32) -> Option<f64> {
+        let age = year.saturating_sub(self.year_born_of(owner));
+        self.contribution_limits
+            .as_ref()
+            .map(|limits| limits.get(kind).value(year, age, self))
+    }
+    /// Rate of inflation (percent) for the specified year
+    pub fn inflation_rate(&self, year: u32) -> f64 {
+        match (&self.inflation_sequence, &self.inflation_schedule) {
+            (Some(rates), _) => rates.get(year).unwrap_or(self.inflation_base),
+            (None, Some(schedule)) => schedule.value(year, Owner::Primary, self),
+            (None, None) => self.inflation_base,
+        }
+    }
+    /// Taxable income where the lowest tax bracket ends for the specified year
+    ///
+    /// With a flat income tax there is no low bracket to fill so zero is returned.
+    pub fn low_bracket_limit(&self, year: u32) -> f64 {
+        match &self.tax_brackets {
+            Some(brackets) => brackets.low_bracket_limit(year, self),
+            None => 0_f64,
+        }
+    }
+    /// Compute the income tax owed on taxable income for the specified year
+    pub fn income_tax(&self, taxable_income: f64, year: u32) -> IncomeTax {
+        match &self.tax_brackets {
+            Some(brackets) => brackets.income_tax(taxable_income, year, self),
+            None => IncomeTax {
+                tax: taxable_income * (self.tax_income / 100_f64),
+                effective_rate: self.tax_income,
+                marginal_rate: self.tax_income,
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use float_cmp::assert_approx_eq;
     use super::*;
+    use crate::inputs::{fixed_with_inflation, ScheduleKey, ScheduleStep};
 
     fn test_settings_values() -> Settings {
         Settings {
@@ -116,6 +249,15 @@ mod tests {
                     high: 80_f64,
                 },
             },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
         }
     }
 
@@ -128,6 +270,32 @@ mod tests {
         assert_eq!(settings.year_end(), 2080);
     }
 
+    #[test]
+    fn household() {
+        let mut settings = test_settings_values();
+        assert_eq!(settings.year_die_of(Owner::Spouse), 2080);
+        assert!(!settings.is_alive(Owner::Spouse, 2010));
+        assert!(!settings.is_survivor(Owner::Primary, 2050));
+
+        settings.spouse = Some(Spouse {
+            age_retire: 60,
+            age_die: 90,
+            year_born: 1985,
+        });
+        assert_eq!(settings.year_retire_of(Owner::Spouse), 2045);
+        assert_eq!(settings.year_die_of(Owner::Spouse), 2075);
+        assert_eq!(settings.year_end(), 2080);
+        assert!(settings.is_alive(Owner::Spouse, 2074));
+        assert!(!settings.is_alive(Owner::Spouse, 2075));
+        assert!(!settings.is_survivor(Owner::Primary, 2074));
+        assert!(settings.is_survivor(Owner::Primary, 2075));
+
+        // the household lasts as long as the person who lives longest
+        settings.spouse.as_mut().unwrap().age_die = 100;
+        assert_eq!(settings.year_end(), 2085);
+        assert!(settings.is_survivor(Owner::Spouse, 2080));
+    }
+
     #[test]
     fn retirement() {
         let settings = test_settings_values();
@@ -135,4 +303,21 @@ mod tests {
         assert_eq!(settings.is_retired(2090), true);
         assert_eq!(settings.is_retired(2040), true);
     }
+
+    #[test]
+    fn inflation() {
+        let mut settings = test_settings_values();
+        assert_approx_eq!(f64, settings.inflation_rate(2010), 5.0);
+        settings.inflation_schedule = Some(RateSchedule {
+            key: ScheduleKey::Year,
+            steps: vec![ScheduleStep {
+                at: 2001,
+                rate: 10.0,
+            }],
+            interpolate: false,
+        });
+        assert_approx_eq!(f64, settings.inflation_rate(2010), 10.0);
+        // the first rate is used before the first step
+        assert_approx_eq!(f64, fixed_with_inflation(100.0, 2002, &settings), 121.0);
+    }
 }
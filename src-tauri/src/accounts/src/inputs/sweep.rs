@@ -0,0 +1,21 @@
+//! User input end of year cash sweep policy
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// What to do with the money left in net at the end of each year
+///
+/// After taxes and expenses are paid, any net above the cash buffer is deposited into the
+/// deposit accounts and a negative net is covered by withdrawing from accounts in the
+/// order listed in `withdrawal_order`.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CashSweep {
+    /// Amount of cash to keep in net before depositing the surplus [in today's dollars]
+    pub cash_buffer: f64,
+    /// Accounts (uuid) that the surplus is split evenly between (pretax accounts are not allowed)
+    pub deposit_accounts: Vec<String>,
+    /// Accounts (uuid) to withdraw from to cover a deficit, in priority order
+    pub withdrawal_order: Vec<String>,
+}
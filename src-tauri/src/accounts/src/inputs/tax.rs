@@ -0,0 +1,218 @@
+//! User input income tax brackets
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{fixed_with_inflation, Settings};
+
+/// Single bracket of a progressive income tax
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxBracket {
+    /// Taxable income where this bracket starts [in today's dollars]
+    pub threshold: f64,
+    /// Marginal tax rate for income in this bracket (percent)
+    pub rate: f64,
+}
+
+/// Progressive income tax table
+///
+/// Thresholds and the standard deduction are in today's dollars and are increased by
+/// the base rate of inflation each year.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxBrackets {
+    /// Tax brackets (the first bracket should start at zero)
+    pub brackets: Vec<TaxBracket>,
+    /// Amount of income that is not taxed [in today's dollars]
+    pub standard_deduction: f64,
+}
+
+/// Income tax owed for a year
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq)]
+pub struct IncomeTax {
+    /// Amount of tax owed
+    pub tax: f64,
+    /// Tax owed as a percent of taxable income
+    pub effective_rate: f64,
+    /// Tax rate applied to the next dollar of taxable income (percent)
+    pub marginal_rate: f64,
+}
+
+impl TaxBrackets {
+    /// Taxable income where the lowest bracket ends (including the standard deduction) for the specified year
+    pub fn low_bracket_limit(&self, year: u32, settings: &Settings) -> f64 {
+        let mut thresholds: Vec<f64> = self.brackets.iter().map(|b| b.threshold).collect();
+        thresholds.sort_by(|a, b| a.total_cmp(b));
+        match thresholds.get(1) {
+            Some(threshold) => fixed_with_inflation(self.standard_deduction + threshold, year, settings),
+            None => f64::INFINITY,
+        }
+    }
+    /// Taxable income where the bracket with `rate` ends (including the standard deduction) for the specified year
+    ///
+    /// None is returned when there is no bracket with that rate.
+    pub fn bracket_limit(&self, rate: f64, year: u32, settings: &Settings) -> Option<f64> {
+        let mut brackets = self.brackets.clone();
+        brackets.sort_by(|a, b| a.threshold.total_cmp(&b.threshold));
+
+        let idx = brackets.iter().position(|b| (b.rate - rate).abs() < 1e-9)?;
+        match brackets.get(idx + 1) {
+            Some(next) => Some(fixed_with_inflation(self.standard_deduction + next.threshold, year, settings)),
+            None => Some(f64::INFINITY),
+        }
+    }
+    /// Compute the income tax owed on taxable income for the specified year
+    pub fn income_tax(&self, taxable_income: f64, year: u32, settings: &Settings) -> IncomeTax {
+        let deduction = fixed_with_inflation(self.standard_deduction, year, settings);
+        let income = taxable_income - deduction;
+
+        let mut brackets = self.brackets.clone();
+        brackets.sort_by(|a, b| a.threshold.total_cmp(&b.threshold));
+
+        let mut tax = 0_f64;
+        let mut marginal_rate = 0_f64;
+        for (idx, bracket) in brackets.iter().enumerate() {
+            let low = fixed_with_inflation(bracket.threshold, year, settings);
+            if income <= low {
+                break;
+            }
+            let high = match brackets.get(idx + 1) {
+                Some(next) => fixed_with_inflation(next.threshold, year, settings),
+                None => f64::INFINITY,
+            };
+            tax += (income.min(high) - low) * bracket.rate / 100_f64;
+            marginal_rate = bracket.rate;
+        }
+
+        IncomeTax {
+            tax,
+            effective_rate: match taxable_income > 0_f64 {
+                true => tax / taxable_income * 100_f64,
+                false => 0_f64,
+            },
+            marginal_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{Span, SsaSettings};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 100,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: Some(test_brackets()),
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    fn test_brackets() -> TaxBrackets {
+        TaxBrackets {
+            brackets: vec![
+                TaxBracket {
+                    threshold: 0_f64,
+                    rate: 10_f64,
+                },
+                TaxBracket {
+                    threshold: 10_000_f64,
+                    rate: 20_f64,
+                },
+                TaxBracket {
+                    threshold: 50_000_f64,
+                    rate: 30_f64,
+                },
+            ],
+            standard_deduction: 5_000_f64,
+        }
+    }
+
+    #[test]
+    fn brackets() {
+        let settings = test_settings_values();
+        let brackets = test_brackets();
+
+        // below the standard deduction
+        let tax = brackets.income_tax(4_000_f64, 2000, &settings);
+        assert_approx_eq!(f64, tax.tax, 0_f64);
+        assert_approx_eq!(f64, tax.effective_rate, 0_f64);
+        assert_approx_eq!(f64, tax.marginal_rate, 0_f64);
+
+        // 10% of 10,000 plus 20% of 25,000
+        let tax = brackets.income_tax(40_000_f64, 2000, &settings);
+        assert_approx_eq!(f64, tax.tax, 6_000_f64);
+        assert_approx_eq!(f64, tax.effective_rate, 15_f64);
+        assert_approx_eq!(f64, tax.marginal_rate, 20_f64);
+
+        // 10% of 10,000 plus 20% of 40,000 plus 30% of 45,000
+        let tax = brackets.income_tax(100_000_f64, 2000, &settings);
+        assert_approx_eq!(f64, tax.tax, 22_500_f64);
+        assert_approx_eq!(f64, tax.marginal_rate, 30_f64);
+    }
+
+    #[test]
+    fn brackets_indexed_to_inflation() {
+        let settings = test_settings_values();
+        let brackets = test_brackets();
+
+        // one year of 5% inflation raises the deduction and thresholds by 5%
+        let tax = brackets.income_tax(42_000_f64, 2001, &settings);
+        assert_approx_eq!(f64, tax.tax, 6_300_f64, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn low_bracket_limit() {
+        let settings = test_settings_values();
+        let brackets = test_brackets();
+        assert_approx_eq!(f64, brackets.low_bracket_limit(2000, &settings), 15_000_f64);
+        assert_approx_eq!(f64, settings.low_bracket_limit(2000), 15_000_f64);
+    }
+
+    #[test]
+    fn bracket_limit() {
+        let settings = test_settings_values();
+        let brackets = test_brackets();
+        assert_approx_eq!(f64, brackets.bracket_limit(20_f64, 2000, &settings).unwrap(), 55_000_f64);
+        assert_eq!(brackets.bracket_limit(30_f64, 2000, &settings), Some(f64::INFINITY));
+        assert_eq!(brackets.bracket_limit(15_f64, 2000, &settings), None);
+    }
+
+    #[test]
+    fn settings_flat_tax() {
+        let mut settings = test_settings_values();
+        settings.tax_brackets = None;
+        let tax = settings.income_tax(40_000_f64, 2000);
+        assert_approx_eq!(f64, tax.tax, 8_000_f64);
+        assert_approx_eq!(f64, tax.effective_rate, 20_f64);
+        assert_approx_eq!(f64, tax.marginal_rate, 20_f64);
+    }
+}
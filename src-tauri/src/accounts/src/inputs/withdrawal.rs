@@ -19,6 +19,22 @@ pub enum WithdrawalOptions {
     ColFracOfSavings,
     /// Withdrawals are manually calculated or are none
     Other,
+    /// Withdrawals are decided by the household drawdown planner (see [Settings](super::Settings))
+    Planned,
+}
+
+/// Strategy used by the household drawdown planner to split the cost of living across accounts
+#[derive(TS, Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DrawdownStrategy {
+    /// Take from taxable accounts first, then tax deferred accounts, then tax free (Roth) accounts
+    TaxableFirst,
+    /// Take from every account in proportion to its balance
+    Proportional,
+    /// Take from tax deferred accounts until taxable income reaches the top of the lowest
+    /// tax bracket, then cover the rest taxable first
+    FillLowBracket,
 }
 
 /// used to populate account dropdown for tax status selection
@@ -40,10 +56,14 @@ pub enum TaxStatus {
     /// Withdrawals count as income but do not to taxable income.
     /// aka 1
     ContributeTaxedEarningsTaxed,
-    // not implemented.
-    // NOT IMPLEMENTED ## 2=payed with taxed income, earnings are taxed in year taken out as capital gains, withdrawals are not taxed
-    // aka 2
-    // NotImpliemented,
+    /// Paid with taxed income, earnings are taxed in year taken out as capital gains (use with brokerage accounts)
+    ///
+    /// Contributions count as an expense (will be subtracted from net for the year).
+    /// Contributions do not impact taxable income (as they are made with dollars that have already been taxed).
+    /// Contributions are tracked as cost basis, only the share of a withdrawal that is a gain is taxed as capital gains.
+    /// Withdrawals count as income but do not add to taxable income.
+    /// aka 2
+    ContributeTaxedEarningsTaxedWhenUsed,
     /// Paid with pretax income and taxed in year of use as income
     ///
     /// Contributions count as an expense (will be subtracted from net for the year).
@@ -69,6 +69,10 @@ pub enum YearSuggestion {
     /// When you plan to die
     //#[serde(rename="yearDie")]
     YearDie,
+    /// When your spouse plans to retire
+    SpouseYearRetire,
+    /// When your spouse plans to die
+    SpouseYearDie,
     /// Last year of the simulation
     //#[serde(rename="yearEnd")]
     YearEnd,
@@ -87,6 +91,8 @@ impl YearSuggestion {
             Self::YearStart => settings.year_start(),
             Self::YearRetire => settings.year_retire(),
             Self::YearDie => settings.year_die(),
+            Self::SpouseYearRetire => settings.year_retire_of(settings::Owner::Spouse),
+            Self::SpouseYearDie => settings.year_die_of(settings::Owner::Spouse),
             Self::YearEnd => settings.year_end(),
             Self::IncomeLink => match eval_type {
                 YearEvalType::StartIn => linked_dates.unwrap_or_default().year_in.unwrap_or_default().start,
@@ -123,6 +129,15 @@ mod tests {
                     high: 80_f64,
                 },
             },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
         }
     }
 
@@ -180,4 +195,19 @@ mod tests {
         assert_eq!(w7.value(&settings, Some(dates), YearEvalType::StartOut), 1900);
         assert_eq!(w8.value(&settings, Some(dates), YearEvalType::EndOut), 1901);
     }
+
+    #[test]
+    fn year_input_spouse() {
+        let mut settings = test_settings_values();
+        settings.spouse = Some(settings::Spouse {
+            age_retire: 60,
+            age_die: 90,
+            year_born: 1985,
+        });
+        let w1 = YearInput::Suggested(YearSuggestion::SpouseYearRetire);
+        let w2: YearInput = serde_json::from_str(r#"{"base": "spouseYearDie", "delta": -1}"#).unwrap();
+
+        assert_eq!(w1.value(&settings, None, YearEvalType::StartIn), 2045);
+        assert_eq!(w2.value(&settings, None, YearEvalType::EndIn), 2074);
+    }
 }
@@ -11,18 +11,23 @@ use image::{ImageBuffer, Rgba};
 
 mod inputs;
 use inputs::{
-    ContributionOptions, EmployerMatch, ExpenseOptions, PaymentOptions, PercentInput, Settings,
-    TaxStatus, WithdrawalOptions, YearEvalType, YearInput
+    ContributionOptions, EmployerMatch, ExpenseOptions, LimitKind, Owner, PaymentOptions,
+    PercentInput, Settings, TaxStatus, WithdrawalOptions, YearEvalType, YearInput
 };
 // re-exported for use outside this lib
-pub use inputs::UserData;
+pub use inputs::{MonteCarloOptions, UserData};
 
 mod simulation;
 use simulation::{
-    LoanTables, SavingsTables, SingleTable, Table, YearRange, YearlyImpact,
+    AmortizationSummary, LoanTables, Penalty, SavingsTables, SingleTable, Table, YearRange,
+    YearlyImpact,
 };
 // re-exported for use outside this lib
-pub use simulation::{Dates, YearlyTotals, PlotDataSet};
+pub use simulation::{
+    run, run_backtest, run_conversion_comparison, run_monte_carlo, AnalysisResults, BacktestResult,
+    ConversionComparison, Dates, MonteCarloResult, PlotDataSet, Simulation, SimulationError,
+    SimulationResult, YearlyTotals,
+};
 
 mod plot;
 use plot::{scatter_plot_buf, scatter_plot_file};
@@ -30,6 +35,9 @@ use plot::{scatter_plot_buf, scatter_plot_file};
 mod college;
 use college::College;
 
+mod event;
+use event::Event;
+
 mod expense;
 use expense::Expense;
 
@@ -45,6 +53,12 @@ use loan::Loan;
 mod mortgage;
 use mortgage::Mortgage;
 
+mod pension;
+use pension::Pension;
+
+mod real_estate;
+use real_estate::RealEstate;
+
 mod retirement;
 use retirement::Retirement;
 
@@ -66,6 +80,11 @@ pub trait Account: std::fmt::Debug {
     /// Return link id if the account is linked to another account
     fn link_id(&self) -> Option<String>;
 
+    /// Return who in the household the account belongs to
+    fn owner(&self) -> Owner {
+        Owner::Primary
+    }
+
     /// Initialize analysis tables with a value for every year in years.  Fill with
     /// values from user data file first then backfill with 0 for years that do not
     /// have a value in user data.  Also initializes the dates used for analysis.
@@ -78,6 +97,26 @@ pub trait Account: std::fmt::Debug {
     // /// Return the value for the specified year
     fn get_value(&self, year: u32) -> Option<f64>;
 
+    /// Return the balance sheet value of the account for the specified year
+    ///
+    /// Assets are positive and liabilities (money owed) are negative.  Accounts that only move
+    /// money in or out each year (such as income and expenses) are not on the balance sheet.
+    fn balance_sheet_value(&self, _year: u32) -> Option<f64> {
+        None
+    }
+
+    /// Return the account that money from this account is deposited in or withdrawn from
+    ///
+    /// The amount moved in a year is the value of this account for that year (used by events).
+    fn destination(&self) -> Option<String> {
+        None
+    }
+
+    /// Return the value passed to accounts that link to this account for the specified year
+    fn linked_value(&self, year: u32, _settings: &Settings) -> Option<f64> {
+        self.get_value(year)
+    }
+
     /// Return start_in and end_in
     fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange>;
 
@@ -86,14 +125,87 @@ pub trait Account: std::fmt::Debug {
 
     /// Compute the value for a year (this needs to be done in time order)
     ///  year: year to compute values for
-    ///  income: total income for that year
+    ///  linked_value: value of the linked account for that year (if the account has a link ID)
+    ///  totals: totals across all accounts for that year
     fn simulate(
         &mut self,
         year: u32,
+        linked_value: Option<f64>,
         totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>>;
 
+    /// Use a percent return for each year instead of the yearly return input
+    ///
+    /// Used to run the plan against a sequence of random returns.  Accounts that do not
+    /// earn a return ignore the sequence.
+    fn set_return_sequence(&mut self, _returns: Table<u32>) {}
+
+    /// Return how cashflow in the account is treated for tax purposes (if the account has a tax status)
+    fn tax_status(&self) -> Option<TaxStatus> {
+        None
+    }
+
+    /// Return how withdrawals from the account are determined (if the account has withdrawals)
+    fn withdrawal_type(&self) -> Option<WithdrawalOptions> {
+        None
+    }
+
+    /// Return how contributions to the account are determined (if the account has contributions)
+    fn contribution_type(&self) -> Option<ContributionOptions> {
+        None
+    }
+
+    /// Return the kind of contribution limit the account shares with its owner's other accounts
+    fn limit_kind(&self) -> Option<LimitKind> {
+        None
+    }
+
+    /// Return the most that can be contributed to the account in the specified year (if the account has a limit)
+    fn contribution_limit(&self, year: u32, settings: &Settings) -> Option<f64> {
+        self.limit_kind()
+            .and_then(|kind| settings.contribution_limit(kind, self.owner(), year))
+    }
+
+    /// Return the part of the contribution limit the other accounts of the same owner and kind
+    /// have not used yet in the specified year (if the account has a limit)
+    fn contribution_room(
+        &self,
+        year: u32,
+        totals: &YearlyTotals,
+        settings: &Settings,
+    ) -> Option<f64> {
+        let kind = self.limit_kind()?;
+        self.contribution_limit(year, settings)
+            .map(|limit| limit - totals.get_limited_contribution(self.owner(), kind, year))
+    }
+
+    /// Return the amount contributed to the account toward its limit in the specified year
+    fn limited_contribution(&self, _year: u32) -> f64 {
+        0_f64
+    }
+
+    /// Move money from net into the account after a year is simulated (used by the cash sweep)
+    ///
+    /// The deposit is capped at `limit` (if there is one) and the amount over it is recorded as
+    /// a contribution shortfall.  Accounts that can not hold swept cash return an error.
+    fn deposit(
+        &mut self,
+        _year: u32,
+        _amount: f64,
+        _limit: Option<f64>,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        Err(String::from("This account type does not accept deposits.").into())
+    }
+
+    /// Take up to `amount` out of the account after a year is simulated (used by the cash sweep)
+    ///
+    /// The withdrawal is limited by the account value.  The amount withdrawn is returned as the
+    /// income of the impact.  Accounts that can not be withdrawn from return an error.
+    fn withdraw(&mut self, _year: u32, _amount: f64) -> Result<YearlyImpact, Box<dyn Error>> {
+        Err(String::from("This account type does not allow withdrawals.").into())
+    }
+
     /// Save the account simulation results to a csv file
     fn write(&self, filepath: String);
 
@@ -106,6 +218,11 @@ pub trait Account: std::fmt::Debug {
     /// Get plot data for UI plotting
     fn get_plot_data(&self) -> Vec<PlotDataSet>;
 
+    /// Return the payoff date and interest totals of the account's amortization schedule (if any)
+    fn amortization_summary(&self) -> Option<AmortizationSummary> {
+        None
+    }
+
     /// Return string json of the inputs for the account
     fn get_inputs(&self) -> String;
 }
@@ -117,6 +234,8 @@ pub trait AccountSavings: Account {
     /// Calculate the withdrawal amount for the specified year.  This value is limited by the 
     /// account value for that year (so the account can not become overdrawn).
     fn get_withdrawal(&self, year:u32, totals: &YearlyTotals, settings: &Settings ) -> f64;
+    /// Percent return earned in the specified year (from the return sequence when one is set)
+    fn get_yearly_return(&self, year:u32, settings: &Settings ) -> f64;
 }
 
 /// Trait for accounts of various types that have payments (loans)
@@ -132,9 +251,12 @@ pub trait AccountExpense: Account {
 }
 
 /// List of the types of accounts that are available
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
 pub enum AccountType {
     Income,
+    Pension,
+    Event,
     Ssa,
     Retirement,
     Hsa,
@@ -142,14 +264,17 @@ pub enum AccountType {
     Expense,
     Loan,
     Mortgage,
+    RealEstate,
     Savings,
 }
 
-/// String representation of the enum value
+/// String representation of the enum value (the same name it is serialized with)
 impl AccountType {
     pub fn to_string(&self) -> String {
         match self {
             AccountType::Income => "income".to_string(),
+            AccountType::Pension => "pension".to_string(),
+            AccountType::Event => "event".to_string(),
             AccountType::Ssa => "ssa".to_string(),
             AccountType::Retirement => "retirement".to_string(),
             AccountType::Hsa => "hsa".to_string(),
@@ -157,15 +282,19 @@ impl AccountType {
             AccountType::Expense => "expense".to_string(),
             AccountType::Loan => "loan".to_string(),
             AccountType::Mortgage => "mortgage".to_string(),
+            AccountType::RealEstate => "realEstate".to_string(),
             AccountType::Savings => "savings".to_string(),
         }
     }
     pub fn order() -> Vec<AccountType> {
         vec![
             AccountType::Income,
+            AccountType::Pension, // Pensions must be run before SSA to be counted in combined income
+            AccountType::Event,
             AccountType::Ssa,
             AccountType::Expense,
             AccountType::Hsa, // Expenses must be run before HSA to be able to compute HSA withdrawal amount
+            AccountType::RealEstate, // Property values must be known before the mortgage computes loan to value
             AccountType::Mortgage,
             AccountType::Loan,
             AccountType::College,
@@ -182,6 +311,8 @@ impl AccountType {
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum AccountWrapper {
     Income(Income<String>),
+    Pension(Pension<String>),
+    Event(Event<String>),
     Ssa(Ssa),
     Retirement(Retirement<String>),
     Hsa(Hsa<String>),
@@ -189,6 +320,7 @@ pub enum AccountWrapper {
     Expense(Expense<String>),
     Loan(Loan<String>),
     Mortgage(Mortgage<String>),
+    RealEstate(RealEstate<String>),
     Savings(Savings<String>),
 }
 
@@ -196,6 +328,8 @@ impl AccountWrapper {
     pub fn to_account_object(self) -> Box<dyn Account> {
         match self {
             AccountWrapper::Income(account) => Box::new(Income::<u32>::from(account)),
+            AccountWrapper::Pension(account) => Box::new(Pension::<u32>::from(account)),
+            AccountWrapper::Event(account) => Box::new(Event::<u32>::from(account)),
             AccountWrapper::Ssa(account) => Box::new(account),
             AccountWrapper::Retirement(account) => Box::new(Retirement::<u32>::from(account)),
             AccountWrapper::Hsa(account) => Box::new(Hsa::<u32>::from(account)),
@@ -203,15 +337,19 @@ impl AccountWrapper {
             AccountWrapper::Expense(account) => Box::new(Expense::<u32>::from(account)),
             AccountWrapper::Loan(account) => Box::new(Loan::<u32>::from(account)),
             AccountWrapper::Mortgage(account) => Box::new(Mortgage::<u32>::from(account)),
+            AccountWrapper::RealEstate(account) => Box::new(RealEstate::<u32>::from(account)),
             AccountWrapper::Savings(account) => Box::new(Savings::<u32>::from(account)),
         }
     }
     pub fn order() -> Vec<AccountType> {
         vec![
             AccountType::Income,
+            AccountType::Pension, // Pensions must be run before SSA to be counted in combined income
+            AccountType::Event,
             AccountType::Ssa,
             AccountType::Expense,
             AccountType::Hsa, // Expenses must be run before HSA to be able to compute HSA withdrawal amount
+            AccountType::RealEstate, // Property values must be known before the mortgage computes loan to value
             AccountType::Mortgage,
             AccountType::Loan,
             AccountType::College,
@@ -228,6 +366,8 @@ impl AccountWrapper {
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum AccountWrapperUI {
     Income(Income<u32>),
+    Pension(Pension<u32>),
+    Event(Event<u32>),
     Ssa(Ssa),
     Retirement(Retirement<u32>),
     Hsa(Hsa<u32>),
@@ -235,6 +375,7 @@ pub enum AccountWrapperUI {
     Expense(Expense<u32>),
     Loan(Loan<u32>),
     Mortgage(Mortgage<u32>),
+    RealEstate(RealEstate<u32>),
     Savings(Savings<u32>),
 }
 
@@ -5,7 +5,8 @@ use std::error::Error;
 use ts_rs::TS;
 use image::{ImageBuffer, Rgba};
 
-use crate::inputs::fixed_with_inflation;
+use crate::inputs::{closing_costs, fixed_with_inflation, loan_rates, loan_term, LoanChange};
+use crate::simulation::level_payment;
 use account_payment_derive::AccountPayment;
 
 use super::*;
@@ -27,8 +28,12 @@ pub struct Loan<T: std::cmp::Ord> {
     payment_type: PaymentOptions,
     /// How much money should be payed each year (either as a percentage or a fixed dollar amount) [in today's dollars]
     payment_value: f64,
+    /// Years left on the loan at the start of the simulation (used by amortized payments)
+    term: Option<u32>,
     /// Interest rate on borrowed money. This is an APR this is then compounded based on the compound time setting.  Used for LOAN and MORTGAGE account types.
     rate: PercentInput,
+    /// Dated changes to the rate or term (such as a refinance or an adjustable rate reset)
+    changes: Option<Vec<LoanChange>>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -38,6 +43,15 @@ pub struct Loan<T: std::cmp::Ord> {
     /// Calculated date values as a year based on input values
     #[serde(skip)]
     dates: Dates,
+    /// Interest rate (APR) in each year after applying the changes
+    #[serde(skip)]
+    rates: Table<u32>,
+    /// Years left on the loan (only used with amortized payments)
+    #[serde(skip)]
+    years_left: Option<u32>,
+    /// Level payment that pays off the balance over the years left (only used with amortized payments)
+    #[serde(skip)]
+    amortized_payment: f64,
 }
 
 impl From<Loan<String>> for Loan<u32> {
@@ -49,10 +63,15 @@ impl From<Loan<String>> for Loan<u32> {
             end_out: other.end_out,
             payment_type: other.payment_type,
             payment_value: other.payment_value,
+            term: other.term,
             rate: other.rate,
+            changes: other.changes,
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
+            rates: other.rates,
+            years_left: other.years_left,
+            amortized_payment: other.amortized_payment,
         }
     }
 }
@@ -83,6 +102,15 @@ impl Account for Loan<u32> {
             &Table::default(),
             &Table::default(),
         );
+        self.rates = loan_rates(&self.rate, self.changes(), self.owner(), settings);
+        self.years_left = match (self.payment_type, self.term) {
+            (PaymentOptions::Amortized, Some(term)) => Some(term),
+            (PaymentOptions::Amortized, None) => {
+                return Err(String::from("Amortized payments require a term").into())
+            }
+            _ => None,
+        };
+        self.amortized_payment = 0_f64;
         self.dates = Dates {
             year_in: self.get_range_in(settings, linked_dates),
             year_out: self.get_range_out(settings, linked_dates),
@@ -92,6 +120,9 @@ impl Account for Loan<u32> {
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year).map(|balance| -balance)
+    }
     fn get_range_in(
         &self,
         _settings: &Settings,
@@ -143,6 +174,7 @@ impl Account for Loan<u32> {
     fn simulate(
         &mut self,
         year: u32,
+        _linked_value: Option<f64>,
         _totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -154,15 +186,29 @@ impl Account for Loan<u32> {
         }
 
         // Calculate interest
-        result.interest =
-            self.analysis.value.get(year).unwrap() * self.rate.value(settings) / 100_f64;
+        let balance = self.analysis.value.get(year).unwrap();
+        let rate = self.rate_in(year, settings);
+        result.interest = balance * rate / 100_f64;
 
         // Add interest to interest and value tables
         self.analysis.interest.update(year, result.interest);
         self.analysis.value.update(year, result.interest);
 
+        // A change to the term restarts the amortized payments
+        let term = loan_term(self.changes(), year, settings);
+        if let (Some(_), Some(term)) = (self.years_left, term) {
+            self.years_left = Some(term);
+        }
+
         // Calculate payment amount
         if self.dates.year_out.unwrap().contains(year) {
+            if let Some(years_left) = self.years_left {
+                self.amortized_payment = match years_left {
+                    0 => balance + result.interest,
+                    _ => level_payment(balance, rate, years_left),
+                };
+                self.years_left = Some(years_left.saturating_sub(1));
+            }
             result.payment = self.get_payment(year, settings);
         }
 
@@ -175,16 +221,31 @@ impl Account for Loan<u32> {
         }
 
         Ok(YearlyImpact {
-            expense: result.payment,
+            expense: result.payment + closing_costs(self.changes(), year, settings),
             healthcare_expense: 0_f64,
             col: 0_f64,
             saving: 0_f64,
             income_taxable: 0_f64,
+            capital_gains: 0_f64,
             income: 0_f64,
             hsa: 0_f64,
+            penalty: 0_f64,
         })
     }
     fn write(&self, filepath: String) {
         self.analysis.write(filepath);
     }
 }
+
+impl Loan<u32> {
+    /// Dated changes to the rate or term
+    fn changes(&self) -> &[LoanChange] {
+        self.changes.as_deref().unwrap_or_default()
+    }
+    /// Interest rate (APR) in effect for the year
+    fn rate_in(&self, year: u32, settings: &Settings) -> f64 {
+        self.rates
+            .get(year)
+            .unwrap_or_else(|| self.rate.value(year, self.owner(), settings))
+    }
+}
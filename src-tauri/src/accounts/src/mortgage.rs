@@ -5,7 +5,13 @@ use std::error::Error;
 use ts_rs::TS;
 use image::{ImageBuffer, Rgba};
 
-use crate::inputs::fixed_with_inflation;
+use crate::inputs::{
+    closing_costs, fixed_with_inflation, loan_rates, loan_term, Amortization, Escrow, LoanChange,
+};
+use crate::simulation::{
+    amortize, level_payment, monthly_rate, summarize, write_schedule, AmortizationRow,
+    AmortizationSummary,
+};
 use account_payment_derive::AccountPayment;
 
 use super::*;
@@ -27,18 +33,28 @@ pub struct Mortgage<T: std::cmp::Ord> {
     payment_type: PaymentOptions,
     /// How much money should be payed each year (either as a percentage or a fixed dollar amount) [in today's dollars]
     payment_value: f64,
+    /// Years left on the loan at the start of the simulation (used by amortized payments)
+    term: Option<u32>,
     /// Interest rate on borrowed money. This is an APR this is then compounded based on the compound time setting.  Used for LOAN and MORTGAGE account types.
     rate: PercentInput,
+    /// Dated changes to the rate or term (such as a refinance or an adjustable rate reset)
+    changes: Option<Vec<LoanChange>>,
     /// Number of times per year that interest is compounded. (1=yearly, 12=monthly)
     compound_time: f64,
     /// Mortgage insurance payment expressed as a yearly fixed number [in today's dollars]
     mortgage_insurance: f64,
     /// Loan to Value amount when mortgage insurance is no longer pulled from payment.  Since monthly payment does not change over time, after the insurance is done there is more money going to the principal each payment
     ltv_limit: f64,
-    /// Amount of money going into escrow every year to pay for property tax.  This number is currently assumed to be constant (ie property taxes do not increase) [in today's dollars]
+    /// Amount of money going into escrow every year to pay for property tax.  This number is assumed to be constant (ie property taxes do not increase) and is only used when escrow is not set [in today's dollars]
     escrow_value: f64,
-    /// Current value of the home.  This is used to compute loan to value [in today's dollars]
+    /// Property tax and homeowner insurance that grow over time and are still paid after the mortgage is paid off
+    escrow: Option<Escrow>,
+    /// Current value of the home.  This is used to compute loan to value when there is no property link [in today's dollars]
     home_value: f64,
+    /// Link to the real estate account the mortgage is for.  Its value is used to compute loan to value.
+    property_link: Option<String>,
+    /// Compute the mortgage month by month from the rate and term instead of using payment_type and payment_value
+    amortization: Option<Amortization>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -48,6 +64,24 @@ pub struct Mortgage<T: std::cmp::Ord> {
     /// Calculated date values as a year based on input values
     #[serde(skip)]
     dates: Dates,
+    /// Interest rate (APR) in each year after applying the changes
+    #[serde(skip)]
+    rates: Table<u32>,
+    /// Years left on the loan (only used with amortized payments)
+    #[serde(skip)]
+    years_left: Option<u32>,
+    /// Level payment that pays off the balance over the years left (only used with amortized payments)
+    #[serde(skip)]
+    amortized_payment: f64,
+    /// Monthly payment schedule (only used with amortization)
+    #[serde(skip)]
+    schedule: Vec<AmortizationRow>,
+    /// Monthly payment schedule without the extra principal (only used with amortization)
+    #[serde(skip)]
+    baseline: Vec<AmortizationRow>,
+    /// Payoff date and interest saved by the extra principal (only used with amortization)
+    #[serde(skip)]
+    summary: Option<AmortizationSummary>,
 }
 
 impl From<Mortgage<String>> for Mortgage<u32> {
@@ -59,15 +93,26 @@ impl From<Mortgage<String>> for Mortgage<u32> {
             end_out: other.end_out,
             payment_type: other.payment_type,
             payment_value: other.payment_value,
+            term: other.term,
             rate: other.rate,
+            changes: other.changes,
             compound_time: other.compound_time,
             mortgage_insurance: other.mortgage_insurance,
             ltv_limit: other.ltv_limit,
             escrow_value: other.escrow_value,
+            escrow: other.escrow,
             home_value: other.home_value,
+            property_link: other.property_link,
+            amortization: other.amortization,
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
+            rates: other.rates,
+            years_left: other.years_left,
+            amortized_payment: other.amortized_payment,
+            schedule: other.schedule,
+            baseline: other.baseline,
+            summary: other.summary,
         }
     }
 }
@@ -77,7 +122,7 @@ impl Account for Mortgage<u32> {
         AccountType::Mortgage
     }
     fn link_id(&self) -> Option<String> {
-        None
+        self.property_link.clone()
     }
     fn name(&self) -> String {
         self.name.clone()
@@ -87,7 +132,7 @@ impl Account for Mortgage<u32> {
         linked_dates: Option<Dates>,
         settings: &Settings,
     ) -> Result<Vec<(u32, YearlyImpact)>, Box<dyn Error>> {
-        if linked_dates.is_some() {
+        if linked_dates.is_some() && self.property_link.is_none() {
             return Err(String::from("Linked account dates provided but not used").into());
         }
         self.analysis = LoanTables::new(
@@ -97,6 +142,18 @@ impl Account for Mortgage<u32> {
             &Table::default(),
             &Table::default(),
         );
+        self.schedule = Vec::new();
+        self.baseline = Vec::new();
+        self.summary = None;
+        self.rates = loan_rates(&self.rate, self.changes(), self.owner(), settings);
+        self.years_left = match (self.payment_type, self.term) {
+            (PaymentOptions::Amortized, Some(term)) => Some(term),
+            (PaymentOptions::Amortized, None) => {
+                return Err(String::from("Amortized payments require a term").into())
+            }
+            _ => None,
+        };
+        self.amortized_payment = 0_f64;
         self.dates = Dates {
             year_in: self.get_range_in(settings, linked_dates),
             year_out: self.get_range_out(settings, linked_dates),
@@ -106,6 +163,9 @@ impl Account for Mortgage<u32> {
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year).map(|balance| -balance)
+    }
     fn get_range_in(
         &self,
         _settings: &Settings,
@@ -158,9 +218,13 @@ impl Account for Mortgage<u32> {
     fn get_plot_data(&self) -> Vec<PlotDataSet> {
         self.analysis.get_plot_data()
     }
+    fn amortization_summary(&self) -> Option<AmortizationSummary> {
+        self.summary
+    }
     fn simulate(
         &mut self,
         year: u32,
+        linked_value: Option<f64>,
         _totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -171,8 +235,9 @@ impl Account for Mortgage<u32> {
             return Err(String::from("Mortgage account value is negative.").into());
         }
 
-        // Calculate insurance
-        let loan_to_value = self.analysis.value.get(year).unwrap() / self.home_value * 100_f64;
+        // Calculate insurance (using the appreciated value of the linked property when there is one)
+        let home_value = linked_value.unwrap_or(self.home_value);
+        let loan_to_value = self.analysis.value.get(year).unwrap() / home_value * 100_f64;
         let insurance_payment = match loan_to_value > self.ltv_limit {
             true => self.mortgage_insurance,
             false => 0.0,
@@ -182,7 +247,29 @@ impl Account for Mortgage<u32> {
 
         // Calculate escrow
         // Pull escrow out of payment and add to escrow table
-        self.analysis.escrow.update(year, self.escrow_value);
+        let escrow_payment = match &self.escrow {
+            Some(escrow) => escrow.value(year, self.owner(), settings),
+            None => self.escrow_value,
+        };
+        self.analysis.escrow.update(year, escrow_payment);
+
+        // The house is still owned after the mortgage is paid off so escrow is still paid
+        if self.escrow.is_some() && self.analysis.value.get(year).unwrap() <= 0_f64 {
+            self.analysis.payments.update(year, escrow_payment);
+            return Ok(YearlyImpact {
+                expense: escrow_payment + closing_costs(self.changes(), year, settings),
+                ..Default::default()
+            });
+        }
+
+        if let Some(amortization) = self.amortization.clone() {
+            return self.simulate_monthly(
+                year,
+                insurance_payment + escrow_payment,
+                &amortization,
+                settings,
+            );
+        }
 
         // Calculate interest
         // The formula for compound interest is P (1 + r/n)^(nt)
@@ -190,18 +277,36 @@ impl Account for Mortgage<u32> {
         //  r is the interest rate
         //  n is the number of times interest is compounded per time period
         //  t is the number of time periods
-        result.interest = self.analysis.value.get(year).unwrap()
-            * f64::powf(
-                1_f64 + (self.rate.value(settings) / 100_f64) / self.compound_time,
-                self.compound_time,
-            )
-            - self.analysis.value.get(year).unwrap();
+        let balance = self.analysis.value.get(year).unwrap();
+        let yearly_rate = (f64::powf(
+            1_f64 + (self.rate_in(year, settings) / 100_f64) / self.compound_time,
+            self.compound_time,
+        ) - 1_f64)
+            * 100_f64;
+        result.interest = balance * yearly_rate / 100_f64;
 
         self.analysis.interest.update(year, result.interest);
         self.analysis.value.update(year, result.interest);
 
+        // A change to the term restarts the amortized payments
+        let term = loan_term(self.changes(), year, settings);
+        if let (Some(_), Some(term)) = (self.years_left, term) {
+            self.years_left = Some(term);
+        }
+        // Amortized payments cover principal and interest with insurance and escrow paid on top
+        if let Some(years_left) = self.years_left {
+            self.amortized_payment = match years_left {
+                0 => balance + result.interest,
+                _ => level_payment(balance, yearly_rate, years_left),
+            };
+            self.years_left = Some(years_left.saturating_sub(1));
+        }
+
         // Calculate payment available
         result.payment = self.get_payment(year, settings);
+        if self.payment_type == PaymentOptions::Amortized {
+            result.payment += insurance_payment + escrow_payment;
+        }
 
         // Add payment to payment and value tables
         self.analysis.payments.update(year, result.payment);
@@ -209,7 +314,7 @@ impl Account for Mortgage<u32> {
         // Calculate how much of the payment will actually go toward the loan (principal & interest)
         let mut remaining_payment = result.payment; // initial amount that is set to be paid to this loan
         remaining_payment -= insurance_payment; // reduced by the insurance costs for the year
-        remaining_payment -= self.escrow_value; // reduced by escrow / property taxes for the year
+        remaining_payment -= escrow_payment; // reduced by escrow / property taxes for the year
 
         // Apply remaining payment to loan balance
         self.analysis.value.update(year, -remaining_payment);
@@ -219,16 +324,107 @@ impl Account for Mortgage<u32> {
         }
 
         Ok(YearlyImpact {
-            expense: result.payment,
+            expense: result.payment + closing_costs(self.changes(), year, settings),
             healthcare_expense: 0_f64,
             col: 0_f64,
             saving: 0_f64,
             income_taxable: 0_f64,
+            capital_gains: 0_f64,
             income: 0_f64,
             hsa: 0_f64,
+            penalty: 0_f64,
         })
     }
     fn write(&self, filepath: String) {
+        if self.amortization.is_some() {
+            let stem = filepath.strip_suffix(".csv").unwrap_or(&filepath);
+            write_schedule(
+                &self.schedule,
+                self.summary,
+                format!("{}_amortization.csv", stem),
+            );
+        }
         self.analysis.write(filepath);
     }
 }
+
+impl Mortgage<u32> {
+    /// Dated changes to the rate or term
+    fn changes(&self) -> &[LoanChange] {
+        self.changes.as_deref().unwrap_or_default()
+    }
+    /// Interest rate (APR) in effect for the year
+    ///
+    /// Years past the end of the simulation (only reached by the amortization schedule) use the last rate.
+    fn rate_in(&self, year: u32, settings: &Settings) -> f64 {
+        self.rates
+            .get(year)
+            .or_else(|| self.rates.most_recent_value())
+            .unwrap_or_else(|| self.rate.value(year, self.owner(), settings))
+    }
+    /// Simulate a year of monthly payments from the amortization schedule
+    ///
+    /// The schedule is built in the first simulated year from the balance at that time and
+    /// rebuilt from the balance at the start of the year when a change sets a new term.
+    fn simulate_monthly(
+        &mut self,
+        year: u32,
+        insurance_and_escrow: f64,
+        amortization: &Amortization,
+        settings: &Settings,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        let term = loan_term(self.changes(), year, settings);
+        if self.schedule.is_empty() || term.is_some() {
+            let months = term.map_or(amortization.term, |term| term * 12);
+            let balance = self.analysis.value.get(year).unwrap();
+            let baseline_balance = self
+                .baseline
+                .iter()
+                .rfind(|row| row.year < year)
+                .map_or(balance, |row| row.balance);
+            let rate = |year: u32| monthly_rate(self.rate_in(year, settings), self.compound_time);
+            let baseline = amortize(baseline_balance, months, year, 0_f64, rate);
+            let schedule = amortize(balance, months, year, amortization.extra_principal, rate);
+
+            self.baseline.retain(|row| row.year < year);
+            self.baseline.extend(baseline);
+            self.schedule.retain(|row| row.year < year);
+            self.schedule.extend(schedule);
+            self.summary = summarize(&self.schedule, &self.baseline);
+        }
+        let closing_costs = closing_costs(self.changes(), year, settings);
+
+        let months: Vec<&AmortizationRow> = self
+            .schedule
+            .iter()
+            .filter(|row| row.year == year)
+            .collect();
+        // Nothing is owed once the loan is paid off
+        let balance = match months.last() {
+            Some(row) => row.balance,
+            None => {
+                self.analysis.insurance.insert(year, 0_f64);
+                self.analysis.escrow.insert(year, 0_f64);
+                return Ok(YearlyImpact {
+                    expense: closing_costs,
+                    ..Default::default()
+                });
+            }
+        };
+        let interest: f64 = months.iter().map(|row| row.interest).sum();
+        let paid: f64 = months
+            .iter()
+            .map(|row| row.payment + row.extra_principal)
+            .sum();
+        let payment = paid + insurance_and_escrow;
+
+        self.analysis.interest.update(year, interest);
+        self.analysis.payments.update(year, payment);
+        self.analysis.value.insert(year, balance);
+
+        Ok(YearlyImpact {
+            expense: payment + closing_costs,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,285 @@
+//! Defined benefit pension or purchased annuity
+
+use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use ts_rs::TS;
+
+use super::*;
+
+/// Account to represent a pension or annuity that pays a benefit for life
+#[derive(TS, Debug, Clone, Deserialize, Serialize)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Pension<T: std::cmp::Ord> {
+    /// String describing this account
+    name: String,
+    /// Table of benefits received
+    table: Table<T>,
+    /// Calendar year when the benefit starts being paid
+    start_in: YearInput,
+    /// Yearly benefit in the first year it is paid
+    benefit: f64,
+    /// Yearly cost of living adjustment to the benefit as a percent
+    cola: Option<PercentInput>,
+    /// Percent of the benefit your spouse keeps receiving after the owner dies
+    survivor_percent: f64,
+    /// Take this amount once in the start year instead of the yearly benefit
+    lump_sum: Option<f64>,
+    /// Person in the household the account belongs to (you when not set)
+    owner: Option<Owner>,
+    /// General information to store with this account
+    notes: Option<String>,
+    // The following items are used when running the program and are not stored with the user data
+    /// Tables used to store simulation results
+    #[serde(skip)]
+    analysis: SingleTable,
+    /// Calculated date values as a year based on input values
+    #[serde(skip)]
+    dates: Dates,
+}
+
+impl From<Pension<String>> for Pension<u32> {
+    fn from(other: Pension<String>) -> Self {
+        Self {
+            name: other.name,
+            table: other.table.into(),
+            start_in: other.start_in,
+            benefit: other.benefit,
+            cola: other.cola,
+            survivor_percent: other.survivor_percent,
+            lump_sum: other.lump_sum,
+            owner: other.owner,
+            notes: other.notes,
+            analysis: other.analysis,
+            dates: other.dates,
+        }
+    }
+}
+
+impl Account for Pension<u32> {
+    fn type_id(&self) -> AccountType {
+        AccountType::Pension
+    }
+    fn link_id(&self) -> Option<String> {
+        None
+    }
+    fn owner(&self) -> Owner {
+        self.owner.unwrap_or_default()
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn init(
+        &mut self,
+        linked_dates: Option<Dates>,
+        settings: &Settings,
+    ) -> Result<Vec<(u32, YearlyImpact)>, Box<dyn Error>> {
+        if linked_dates.is_some() {
+            return Err(String::from("Linked account dates provided but not used").into());
+        }
+        self.analysis = SingleTable::new(&self.table);
+        self.dates = Dates {
+            year_in: self.get_range_in(settings, linked_dates),
+            year_out: self.get_range_out(settings, linked_dates),
+        };
+        Ok(Vec::new())
+    }
+    fn get_value(&self, year: u32) -> Option<f64> {
+        self.analysis.value.get(year)
+    }
+    fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
+        Some(YearRange {
+            start: self
+                .start_in
+                .value(settings, linked_dates, YearEvalType::StartIn),
+            end: settings.year_end(),
+        })
+    }
+    fn get_range_out(
+        &self,
+        _settings: &Settings,
+        _linked_dates: Option<Dates>,
+    ) -> Option<YearRange> {
+        None
+    }
+    fn get_inputs(&self) -> String {
+        String::from("Hello")
+    }
+    fn plot_to_file(&self, filepath: String, width: u32, height: u32) {
+        scatter_plot_file(
+            filepath,
+            vec![("Amount".into(), &self.analysis.value)],
+            self.name(),
+            width,
+            height,
+        );
+    }
+    fn plot_to_buf(&self, width: u32, height: u32) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        scatter_plot_buf(
+            vec![("Amount".into(), &self.analysis.value)],
+            self.name(),
+            width,
+            height,
+        )
+    }
+    fn get_plot_data(&self) -> Vec<PlotDataSet> {
+        self.analysis.get_plot_data()
+    }
+    fn simulate(
+        &mut self,
+        year: u32,
+        _linked_value: Option<f64>,
+        _totals: &YearlyTotals,
+        settings: &Settings,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        let mut result = WorkingValues::default();
+        self.analysis.add_year(year, false)?;
+
+        if self.dates.year_in.unwrap().contains(year) {
+            result.earning = match self.lump_sum {
+                // The lump sum is paid once if the owner is alive to take it
+                Some(lump_sum) => match year == self.dates.year_in.unwrap().start
+                    && settings.is_alive(self.owner(), year)
+                {
+                    true => lump_sum,
+                    false => 0_f64,
+                },
+                None => self.benefit(year, settings),
+            };
+        }
+
+        // Add benefit to value table
+        self.analysis.value.update(year, result.earning);
+
+        Ok(YearlyImpact {
+            expense: 0_f64,
+            healthcare_expense: 0_f64,
+            col: 0_f64,
+            saving: 0_f64,
+            income_taxable: result.earning,
+            capital_gains: 0_f64,
+            income: result.earning,
+            hsa: 0_f64,
+            penalty: 0_f64,
+        })
+    }
+    fn write(&self, filepath: String) {
+        self.analysis.write(filepath);
+    }
+}
+
+impl Pension<u32> {
+    /// Yearly benefit paid to the owner or to their surviving spouse
+    fn benefit(&self, year: u32, settings: &Settings) -> f64 {
+        let start_in = self.dates.year_in.unwrap().start;
+        // The cost of living adjustment for each year is applied on top of the previous year
+        let benefit = ((start_in + 1)..=year).fold(self.benefit, |benefit, y| {
+            let cola = self
+                .cola
+                .as_ref()
+                .map_or(0_f64, |cola| cola.value(y, self.owner(), settings));
+            benefit * (1_f64 + cola / 100_f64)
+        });
+        if settings.is_alive(self.owner(), year) {
+            benefit
+        } else if settings.is_survivor(self.owner().other(), year) {
+            benefit * self.survivor_percent / 100_f64
+        } else {
+            0_f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{Span, Spouse, SsaSettings};
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 25,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    fn test_pension(lump_sum: Option<f64>) -> Pension<u32> {
+        Pension {
+            name: "Pension".into(),
+            table: Table::default(),
+            start_in: YearInput::ConstantInt(2001),
+            benefit: 1_000_f64,
+            cola: Some(PercentInput::ConstantFloat(10_f64)),
+            survivor_percent: 50_f64,
+            lump_sum,
+            owner: None,
+            notes: None,
+            analysis: SingleTable::default(),
+            dates: Dates::default(),
+        }
+    }
+
+    fn simulate_years(account: &mut Pension<u32>, settings: &Settings) -> Vec<f64> {
+        account.init(None, settings).unwrap();
+        (settings.year_start()..settings.year_end())
+            .map(|year| {
+                account
+                    .simulate(year, None, &YearlyTotals::new(), settings)
+                    .unwrap()
+                    .income_taxable
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pension_benefit() {
+        let mut settings = test_settings_values();
+        settings.spouse = Some(Spouse {
+            year_born: 1980,
+            age_retire: 50,
+            age_die: 28,
+        });
+
+        let benefits = simulate_years(&mut test_pension(None), &settings);
+        assert_approx_eq!(f64, benefits[0], 0_f64);
+        assert_approx_eq!(f64, benefits[1], 1_000_f64);
+        assert_approx_eq!(f64, benefits[2], 1_100_f64, epsilon = 1e-9);
+        // your spouse keeps half of the benefit after you die in 2005
+        assert_approx_eq!(
+            f64,
+            benefits[5],
+            1_000_f64 * 1.1_f64.powi(4) / 2_f64,
+            epsilon = 1e-9
+        );
+
+        let benefits = simulate_years(&mut test_pension(Some(20_000_f64)), &settings);
+        assert_approx_eq!(f64, benefits[1], 20_000_f64);
+        assert_approx_eq!(f64, benefits.iter().sum::<f64>(), 20_000_f64);
+    }
+}
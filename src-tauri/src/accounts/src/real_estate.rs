@@ -0,0 +1,132 @@
+//! Home or other property that changes in value over time
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use image::{ImageBuffer, Rgba};
+use ts_rs::TS;
+
+use super::*;
+
+/// Property that appreciates over time (a mortgage can link to it to use its value for loan to value)
+#[derive(TS, Debug, Clone, Deserialize, Serialize)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RealEstate<T: std::cmp::Ord> {
+    /// String describing this account
+    name: String,
+    /// Table of property value
+    table: Table<T>,
+    /// Value of the property in the first simulated year when the table has no earlier values
+    purchase_price: f64,
+    /// Yearly increase in the value of the property as a percent
+    appreciation: PercentInput,
+    /// General information to store with this account
+    notes: Option<String>,
+    // The following items are used when running the program and are not stored with the user data
+    /// Tables used to store simulation results
+    #[serde(skip)]
+    analysis: SingleTable,
+}
+
+impl From<RealEstate<String>> for RealEstate<u32> {
+    fn from(other: RealEstate<String>) -> Self {
+        Self {
+            name: other.name,
+            table: other.table.into(),
+            purchase_price: other.purchase_price,
+            appreciation: other.appreciation,
+            notes: other.notes,
+            analysis: other.analysis,
+        }
+    }
+}
+
+impl Account for RealEstate<u32> {
+    fn type_id(&self) -> AccountType {
+        AccountType::RealEstate
+    }
+    fn link_id(&self) -> Option<String> {
+        None
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn init(
+        &mut self,
+        linked_dates: Option<Dates>,
+        _settings: &Settings,
+    ) -> Result<Vec<(u32, YearlyImpact)>, Box<dyn Error>> {
+        if linked_dates.is_some() {
+            return Err(String::from("Linked account dates provided but not used").into());
+        }
+        self.analysis = SingleTable::new(&self.table);
+        Ok(Vec::new())
+    }
+    fn get_value(&self, year: u32) -> Option<f64> {
+        self.analysis.value.get(year)
+    }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year)
+    }
+    fn get_range_in(
+        &self,
+        _settings: &Settings,
+        _linked_dates: Option<Dates>,
+    ) -> Option<YearRange> {
+        None
+    }
+    fn get_range_out(
+        &self,
+        _settings: &Settings,
+        _linked_dates: Option<Dates>,
+    ) -> Option<YearRange> {
+        None
+    }
+    fn get_inputs(&self) -> String {
+        String::from("Hello")
+    }
+    fn plot_to_file(&self, filepath: String, width: u32, height: u32) {
+        scatter_plot_file(
+            filepath,
+            vec![("Value".into(), &self.analysis.value)],
+            self.name(),
+            width,
+            height,
+        );
+    }
+    fn plot_to_buf(&self, width: u32, height: u32) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        scatter_plot_buf(
+            vec![("Value".into(), &self.analysis.value)],
+            self.name(),
+            width,
+            height,
+        )
+    }
+    fn get_plot_data(&self) -> Vec<PlotDataSet> {
+        self.analysis.get_plot_data()
+    }
+    fn simulate(
+        &mut self,
+        year: u32,
+        _linked_value: Option<f64>,
+        _totals: &YearlyTotals,
+        settings: &Settings,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        let previous = self.analysis.value.most_recent_value();
+        self.analysis.add_year(year, false)?;
+
+        // The property is worth the purchase price until it has a year to appreciate
+        let value = match previous {
+            Some(value) => {
+                value * (1_f64 + self.appreciation.value(year, self.owner(), settings) / 100_f64)
+            }
+            None => self.purchase_price,
+        };
+        self.analysis.value.insert(year, value);
+
+        Ok(YearlyImpact::default())
+    }
+    fn write(&self, filepath: String) {
+        self.analysis.write(filepath);
+    }
+}
@@ -3,6 +3,7 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::sync::OnceLock;
 use ts_rs::TS;
 use image::{ImageBuffer, Rgba};
 
@@ -11,6 +12,12 @@ use account_savings_derive::AccountSavings;
 
 use super::*;
 
+/// Distribution period for each age used to compute required minimum distributions
+const UNIFORM_LIFETIME_TABLE: &str = include_str!("../data/uniform_lifetime_table.csv");
+
+/// Rows of the uniform lifetime table as (age, distribution period), parsed on first use
+static UNIFORM_LIFETIME: OnceLock<Vec<(u32, f64)>> = OnceLock::new();
+
 /// Generic retirement account type applicable for 401K, Roth IRA, IRA, etc.
 #[derive(TS, Debug, Clone, Deserialize, Serialize, AccountSavings)]
 #[ts(export)]
@@ -52,6 +59,10 @@ pub struct Retirement<T: std::cmp::Ord> {
     income_link: Option<String>,
     /// Percent of your contribution that your employer matches
     matching: Option<EmployerMatch>,
+    /// Kind of account used to look up the contribution limit (contributions are not limited when not set)
+    limit_kind: Option<LimitKind>,
+    /// Person in the household the account belongs to (you when not set)
+    owner: Option<Owner>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -61,6 +72,12 @@ pub struct Retirement<T: std::cmp::Ord> {
     /// Calculated date values as a year based on input values
     #[serde(skip)]
     dates: Dates,
+    /// Percent interest earned in each year used instead of yearly_return (set for Monte Carlo runs)
+    #[serde(skip)]
+    return_sequence: Option<Table<u32>>,
+    /// Penalty for withdrawals made before the qualifying age
+    #[serde(skip)]
+    penalty: Option<Penalty>,
 }
 
 impl From<Retirement<String>> for Retirement<u32> {
@@ -84,9 +101,13 @@ impl From<Retirement<String>> for Retirement<u32> {
             tax_status: other.tax_status,
             income_link: other.income_link,
             matching: other.matching,
+            limit_kind: other.limit_kind,
+            owner: other.owner,
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
+            return_sequence: other.return_sequence,
+            penalty: other.penalty,
         }
     }
 }
@@ -99,6 +120,9 @@ impl Account for Retirement<u32> {
         trace!("Link ID - {:?}", self.income_link);
         self.income_link.clone()
     }
+    fn owner(&self) -> Owner {
+        self.owner.unwrap_or_default()
+    }
     fn name(&self) -> String {
         self.name.clone()
     }
@@ -118,6 +142,10 @@ impl Account for Retirement<u32> {
             year_in: self.get_range_in(settings, linked_dates),
             year_out: self.get_range_out(settings, linked_dates),
         };
+        self.penalty = settings
+            .early_withdrawal_penalties
+            .as_ref()
+            .map(|penalties| penalties.retirement.resolve(settings.year_born_of(self.owner())));
 
         Ok(self
             .table
@@ -137,6 +165,9 @@ impl Account for Retirement<u32> {
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year)
+    }
     fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
         Some(YearRange {
             start: self
@@ -198,9 +229,13 @@ impl Account for Retirement<u32> {
     fn get_plot_data(&self) -> Vec<PlotDataSet> {
         self.analysis.get_plot_data()
     }
+    fn set_return_sequence(&mut self, returns: Table<u32>) {
+        self.return_sequence = Some(returns);
+    }
     fn simulate(
         &mut self,
         year: u32,
+        linked_value: Option<f64>,
         totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -215,7 +250,7 @@ impl Account for Retirement<u32> {
 
         // Calculate earnings
         result.earning =
-            self.analysis.value.get(year).unwrap() * (self.yearly_return.value(settings) / 100.0); // calculate earnings from interest
+            self.analysis.value.get(year).unwrap() * (self.get_yearly_return(year, settings) / 100.0); // calculate earnings from interest
 
         // Add earnings to earnings and value tables
         self.analysis.earnings.update(year, result.earning);
@@ -223,64 +258,39 @@ impl Account for Retirement<u32> {
 
         // Calculate contribution
         if self.dates.year_in.unwrap().contains(year) {
-            result.contribution = self.get_contribution(year, totals, settings);
-
-            match &self.matching {
-                Some(employer_match) => {
-                    if self.income_link.is_some() {
-                        // somehow get the income value from income link
-                    } else {
-                        return Err(
-                            String::from("Matching is set but there is no linked account").into(),
-                        );
-                    }
-
-                    // if (account.matchLimit.length > 1) {
-                    //     // and if it is a complex employer matching (more than one level)
-                    //     if (contribution >= (account.matchLimit[0] / 100 + account.matchLimit[1] / 100) * accounts[account.incomeLink].table[yearCurrent]) {
-                    //         // and if the contribution is above the highest employer matching level
-                    //         employerMatch = accounts[account.incomeLink].table[yearCurrent] * ((account.employerMatch[1] / 100) * (account.matchLimit[1] / 100) + (account.employerMatch[0] / 100) * (account.matchLimit[0] / 100)); // calculate the employer matching based on the match limits
-                    //     } else if (contribution >= (account.matchLimit[0] / 100) * accounts[account.incomeLink].table[yearCurrent]) {
-                    //         // otherwise if the contribution is between the employer matching levels ) {
-                    //         employerMatch = accounts[account.incomeLink].table[yearCurrent] * ((account.employerMatch[0] / 100) * (account.matchLimit[0] / 100) + (account.employerMatch[1] / 100) * (account.matchLimit[1] / 100) * (contribution / accounts[account.incomeLink].table[yearCurrent] - account.matchLimit[0] / 100)); // calculate the employer matching with all the first level and part of the second level
-                    //     } else {
-                    //         employerMatch = contribution * (account.employerMatch[0] / 100); // the employer contribution is computed based on the entire contribution
-                    //     }
-                    // } else {
-                    //     // if it is a simple employer matching (only one level)
-                    //     if (contribution >= account.matchLimit[0] * accounts[account.incomeLink].table[yearCurrent]) {
-                    //         // and if the contribution is above the highest employer matching level
-                    //         employerMatch = accounts[account.incomeLink].table[yearCurrent] * (account.employerMatch[0] / 100) * (account.matchLimit[0] / 100); // calculate the employer matching based on the match limits
-                    //     } else {
-                    //         // otherwise  if below the employer match limit
-                    //         employerMatch = contribution * (account.employerMatch[0] / 100); // the employer contribution is computed based on the entire contribution
-                    //     }
-                    // }
-                    let link_income = 500_f64;
-                    result.employer_contribution = match result.contribution
-                        >= employer_match.limit.value(settings) / 100_f64 * link_income
-                    {
-                        true => {
-                            link_income
-                                * (employer_match.amount.value(settings) / 100_f64)
-                                * (employer_match.limit.value(settings) / 100_f64)
-                        } // calculate the employer matching based on the match limits,
-                        false => {
-                            result.contribution * (employer_match.amount.value(settings) / 100_f64)
-                        } // the employer contribution is computed based on the entire contribution,
-                    };
+            result.contribution = self.analysis.limit_contribution(
+                year,
+                self.get_contribution(year, totals, settings),
+                self.contribution_room(year, totals, settings),
+            );
+
+            if let Some(employer_match) = &self.matching {
+                if self.income_link.is_none() {
+                    return Err(
+                        String::from("Matching is set but there is no linked account").into(),
+                    );
                 }
-                None => {}
+                // Employer matching is computed from the earnings of the linked income account
+                let link_income = linked_value.unwrap_or_default();
+                result.employer_contribution = employer_match.value(
+                    result.contribution,
+                    link_income,
+                    year,
+                    self.owner(),
+                    settings,
+                );
             }
         }
 
         // Add contribution to contribution and value tables
+        self.analysis.contributions.update(year, result.contribution);
         self.analysis
-            .contributions
-            .update(year, result.contribution + result.employer_contribution);
+            .employer_contributions
+            .update(year, result.employer_contribution);
         self.analysis
             .value
             .update(year, result.contribution + result.employer_contribution);
+        self.analysis.add_cost_basis(year, result.contribution);
 
         // Calculate withdrawal
         if self.dates.year_out.unwrap().contains(year) {
@@ -288,9 +298,24 @@ impl Account for Retirement<u32> {
             // result.limit_withdrawal(self.analysis.value.get(year).unwrap());
         }
 
+        // Take at least the required minimum distribution from pretax accounts
+        if self.tax_status == TaxStatus::ContributePretaxTaxedWhenUsed {
+            let age = year.saturating_sub(settings.year_born_of(self.owner()));
+            let rmd = required_minimum_distribution(
+                self.analysis.prior_year_value(year),
+                age,
+                settings.year_born_of(self.owner()),
+            );
+            if rmd > result.withdrawal {
+                result.withdrawal = rmd.min(self.analysis.value.get(year).unwrap());
+            }
+        }
+
         // Add withdrawal to withdrawal table and subtract from value tables
+        let withdrawal_gain = self.analysis.withdraw_cost_basis(year, result.withdrawal);
         self.analysis.withdrawals.update(year, result.withdrawal);
         self.analysis.value.update(year, -result.withdrawal);
+        let penalty = self.early_withdrawal_penalty(year, result.withdrawal, withdrawal_gain);
 
         match self.tax_status {
             // Paid with taxed income, earnings are not taxed, withdrawals are not taxed
@@ -302,10 +327,13 @@ impl Account for Retirement<u32> {
                 expense: result.contribution,
                 healthcare_expense: 0_f64,
                 col: 0_f64,
-                saving: result.contribution + result.earning - result.withdrawal, // delta to savings total for the year
+                saving: result.contribution + result.employer_contribution + result.earning
+                    - result.withdrawal, // delta to savings total for the year
                 income_taxable: 0_f64,
+                capital_gains: 0_f64,
                 income: result.withdrawal,
                 hsa: 0_f64,
+                penalty,
             }),
             // Paid with taxed income, earnings are taxed in year earned as capital gains, withdrawals are not taxed (tax free as long as used for intended purpose)
             //
@@ -316,11 +344,30 @@ impl Account for Retirement<u32> {
                 expense: result.contribution,
                 healthcare_expense: 0_f64,
                 col: 0_f64,
-                saving: result.contribution + result.earning - result.withdrawal, // delta to savings total for the year
-                income_taxable: result.earning,
-                // todo ! something different to account for earnings as cap gains
+                saving: result.contribution + result.employer_contribution + result.earning
+                    - result.withdrawal, // delta to savings total for the year
+                income_taxable: 0_f64,
+                capital_gains: result.earning,
                 income: result.withdrawal,
                 hsa: 0_f64,
+                penalty,
+            }),
+            // Paid with taxed income, earnings are taxed in year taken out as capital gains
+            //
+            // Contributions count as an expense (will be subtracted from net for the year)
+            // Contributions do not impact taxable income (as they are made with dollars that have already been taxed)
+            // The portion of a withdrawal above the cost basis is a capital gain
+            TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => Ok(YearlyImpact {
+                expense: result.contribution,
+                healthcare_expense: 0_f64,
+                col: 0_f64,
+                saving: result.contribution + result.employer_contribution + result.earning
+                    - result.withdrawal, // delta to savings total for the year
+                income_taxable: 0_f64,
+                capital_gains: withdrawal_gain,
+                income: result.withdrawal,
+                hsa: 0_f64,
+                penalty,
             }),
             // Paid with pretax income and taxed in year of use as income
             //
@@ -331,10 +378,13 @@ impl Account for Retirement<u32> {
                 expense: result.contribution,
                 healthcare_expense: 0_f64,
                 col: 0_f64,
-                saving: result.contribution + result.earning - result.withdrawal, // delta to savings total for the year
+                saving: result.contribution + result.employer_contribution + result.earning
+                    - result.withdrawal, // delta to savings total for the year
                 income_taxable: result.withdrawal - result.contribution,
+                capital_gains: 0_f64,
                 income: result.withdrawal,
                 hsa: 0_f64,
+                penalty,
             }),
             // Paid with pretax income and not taxed as income (use with HSA)
             //
@@ -345,14 +395,313 @@ impl Account for Retirement<u32> {
                 expense: result.contribution,
                 healthcare_expense: 0_f64,
                 col: 0_f64,
-                saving: result.contribution + result.earning - result.withdrawal, // delta to savings total for the year
+                saving: result.contribution + result.employer_contribution + result.earning
+                    - result.withdrawal, // delta to savings total for the year
                 income_taxable: 0_f64 - result.contribution,
+                capital_gains: 0_f64,
                 income: result.withdrawal,
                 hsa: 0_f64,
+                penalty,
             }),
         }
     }
+    fn tax_status(&self) -> Option<TaxStatus> {
+        Some(self.tax_status)
+    }
+    fn withdrawal_type(&self) -> Option<WithdrawalOptions> {
+        Some(self.withdrawal_type)
+    }
+    fn contribution_type(&self) -> Option<ContributionOptions> {
+        Some(self.contribution_type)
+    }
+    fn limit_kind(&self) -> Option<LimitKind> {
+        self.limit_kind
+    }
+    fn limited_contribution(&self, year: u32) -> f64 {
+        self.analysis.contributions.get(year).unwrap_or_default()
+    }
+    fn deposit(
+        &mut self,
+        year: u32,
+        amount: f64,
+        limit: Option<f64>,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        let amount = self.analysis.limit_contribution(year, amount, limit);
+        // Swept cash has already been taxed so it is added to the cost basis
+        self.analysis.contributions.update(year, amount);
+        self.analysis.value.update(year, amount);
+        self.analysis.add_cost_basis(year, amount);
+        Ok(YearlyImpact {
+            saving: amount,
+            ..Default::default()
+        })
+    }
+    fn withdraw(&mut self, year: u32, amount: f64) -> Result<YearlyImpact, Box<dyn Error>> {
+        let withdrawal = amount.min(self.analysis.value.get(year).unwrap_or_default()).max(0_f64);
+        let withdrawal_gain = self.analysis.withdraw_cost_basis(year, withdrawal);
+        self.analysis.withdrawals.update(year, withdrawal);
+        self.analysis.value.update(year, -withdrawal);
+        Ok(YearlyImpact {
+            saving: -withdrawal,
+            income_taxable: match self.tax_status {
+                TaxStatus::ContributePretaxTaxedWhenUsed => withdrawal,
+                _ => 0_f64,
+            },
+            capital_gains: match self.tax_status {
+                TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => withdrawal_gain,
+                _ => 0_f64,
+            },
+            income: withdrawal,
+            penalty: self.early_withdrawal_penalty(year, withdrawal, withdrawal_gain),
+            ..Default::default()
+        })
+    }
     fn write(&self, filepath: String) {
         self.analysis.write(filepath);
     }
 }
+
+impl Retirement<u32> {
+    /// Penalty owed on a withdrawal made in the specified year
+    ///
+    /// Withdrawals from pretax accounts are penalized in full.  Only the earnings in a withdrawal
+    /// from a Roth account are penalized and accounts funded with taxed earnings are not penalized.
+    fn early_withdrawal_penalty(&self, year: u32, withdrawal: f64, withdrawal_gain: f64) -> f64 {
+        let penalized = match self.tax_status {
+            TaxStatus::ContributePretaxTaxedWhenUsed => withdrawal,
+            TaxStatus::ContributePretaxUntaxedWhenUsed => withdrawal,
+            TaxStatus::ContributeTaxedEarningsUntaxedWhenUsed => withdrawal_gain,
+            TaxStatus::ContributeTaxedEarningsTaxed => 0_f64,
+            TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => 0_f64,
+        };
+        self.penalty
+            .map_or(0_f64, |penalty| penalty.value(year, penalized))
+    }
+}
+
+/// Age required minimum distributions start based on the year of birth (SECURE 2.0 Act)
+fn rmd_start_age(year_born: u32) -> u32 {
+    match year_born {
+        0..=1950 => 72,
+        1951..=1959 => 73,
+        _ => 75,
+    }
+}
+
+/// Distribution period from the uniform lifetime table (ages past the end of the table use the last period)
+fn uniform_lifetime_divisor(age: u32) -> Option<f64> {
+    let table = UNIFORM_LIFETIME.get_or_init(|| {
+        UNIFORM_LIFETIME_TABLE
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with("age"))
+            .filter_map(|line| {
+                let (age, divisor) = line.split_once(',')?;
+                Some((age.trim().parse().ok()?, divisor.trim().parse().ok()?))
+            })
+            .collect()
+    });
+    table
+        .iter()
+        .rev()
+        .find(|(table_age, _)| *table_age <= age)
+        .map(|(_, divisor)| *divisor)
+}
+
+/// Minimum amount that must be withdrawn from a pretax account in a year
+///
+/// The prior year end balance is divided by the distribution period for the owner's age.
+/// Nothing is required before the statutory starting age.
+fn required_minimum_distribution(prior_balance: f64, age: u32, year_born: u32) -> f64 {
+    if age < rmd_start_age(year_born) || prior_balance <= 0_f64 {
+        return 0_f64;
+    }
+    match uniform_lifetime_divisor(age) {
+        Some(divisor) => prior_balance / divisor,
+        None => 0_f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use crate::inputs::{
+        ContributionLimit, ContributionLimits, EarlyWithdrawalPenalties, EarlyWithdrawalPenalty,
+        Settings, Span, SsaSettings,
+    };
+    use super::*;
+
+    /// Generate settings object for testing (born in 1950 so distributions start in 2022)
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 65,
+            age_die: 100,
+            year_born: 1950,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    /// Generate an account with a balance of 27400 at the end of 2021 and no planned withdrawals
+    fn test_account(tax_status: &str, withdrawal_value: f64) -> Retirement<u32> {
+        let json = format!(
+            r#"{{
+                "name": "IRA",
+                "table": {{"2021": 27400}},
+                "startIn": 2000,
+                "endIn": 2000,
+                "startOut": 2022,
+                "endOut": 2040,
+                "contributionValue": 0,
+                "contributionType": "fixed",
+                "yearlyReturn": 0,
+                "withdrawalType": "fixed",
+                "withdrawalValue": {},
+                "taxStatus": "{}"
+            }}"#,
+            withdrawal_value, tax_status
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn rmd_table() {
+        assert_eq!(rmd_start_age(1950), 72);
+        assert_eq!(rmd_start_age(1955), 73);
+        assert_eq!(rmd_start_age(1960), 75);
+        assert_eq!(uniform_lifetime_divisor(70), None);
+        assert_approx_eq!(f64, uniform_lifetime_divisor(73).unwrap(), 26.5);
+        assert_approx_eq!(f64, uniform_lifetime_divisor(125).unwrap(), 2.0);
+        assert_approx_eq!(f64, required_minimum_distribution(26_500_f64, 72, 1955), 0_f64);
+        assert_approx_eq!(f64, required_minimum_distribution(26_500_f64, 73, 1955), 1_000_f64);
+    }
+
+    #[test]
+    fn rmd_withdrawal() {
+        let settings = test_settings_values();
+        let yearly_totals = YearlyTotals::new();
+
+        // the distribution replaces a smaller withdrawal and is taxed as income
+        let mut account = test_account("contribute_pretax_taxed_when_used", 100_f64);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2022, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, 1_000_f64);
+        assert_approx_eq!(f64, update.income_taxable, 1_000_f64);
+        assert_approx_eq!(f64, account.get_value(2022).unwrap(), 26_400_f64);
+
+        // a larger withdrawal is left alone
+        let mut account = test_account("contribute_pretax_taxed_when_used", 2_000_f64);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2022, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, 2_000_f64);
+
+        // other tax statuses do not have distributions
+        let mut account = test_account("contribute_taxed_earnings_untaxed_when_used", 100_f64);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2022, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, 100_f64);
+    }
+
+    #[test]
+    fn contribution_limit() {
+        let mut settings = test_settings_values();
+        let limit = |limit: f64| ContributionLimit {
+            limit,
+            catch_up: 1_000_f64,
+            catch_up_age: 50,
+        };
+        settings.contribution_limits = Some(ContributionLimits {
+            workplace: limit(20_000_f64),
+            ira: limit(5_000_f64),
+            hsa: limit(3_000_f64),
+        });
+        let yearly_totals = YearlyTotals::new();
+
+        // contributions over the limit (including catch-up at age 50) are capped
+        let mut account = test_account("contribute_pretax_taxed_when_used", 0_f64);
+        account.contribution_value = 10_000_f64;
+        account.limit_kind = Some(LimitKind::Ira);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2000, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.expense, 6_000_f64);
+        assert_approx_eq!(
+            f64,
+            account.analysis.contribution_shortfall.get(2000).unwrap(),
+            4_000_f64
+        );
+
+        // the maximum allowed contributes the limit
+        let mut account = test_account("contribute_pretax_taxed_when_used", 0_f64);
+        account.contribution_type = ContributionOptions::MaxAllowed;
+        account.limit_kind = Some(LimitKind::Workplace);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2000, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.expense, 21_000_f64);
+        assert_approx_eq!(
+            f64,
+            account.analysis.contribution_shortfall.get(2000).unwrap(),
+            0_f64
+        );
+
+        // accounts without a kind are not limited
+        let mut account = test_account("contribute_pretax_taxed_when_used", 0_f64);
+        account.contribution_value = 50_000_f64;
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2000, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.expense, 50_000_f64);
+    }
+
+    #[test]
+    fn early_withdrawal_penalty() {
+        let mut settings = test_settings_values();
+        settings.year_born = 1970;
+        let penalty = EarlyWithdrawalPenalty {
+            age: 59.5,
+            rate: 10_f64,
+        };
+        settings.early_withdrawal_penalties = Some(EarlyWithdrawalPenalties {
+            retirement: penalty.clone(),
+            hsa: penalty,
+        });
+        let yearly_totals = YearlyTotals::new();
+
+        // pretax withdrawals are penalized in full
+        let mut account = test_account("contribute_pretax_taxed_when_used", 2_000_f64);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2022, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.penalty, 200_f64);
+
+        // Roth withdrawals of contributions are not penalized
+        let mut account = test_account("contribute_taxed_earnings_untaxed_when_used", 2_000_f64);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2022, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.penalty, 0_f64);
+
+        // no penalty once you reach the qualifying age
+        let mut account = test_account("contribute_pretax_taxed_when_used", 2_000_f64);
+        account.init(None, &settings).unwrap();
+        let update = account.simulate(2030, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.penalty, 0_f64);
+    }
+}
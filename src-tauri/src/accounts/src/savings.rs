@@ -45,6 +45,8 @@ pub struct Savings<T: std::cmp::Ord> {
     withdrawal_value: f64,
     /// How cashflow in this account is treated for tax purposes
     tax_status: TaxStatus,
+    /// Person in the household the account belongs to (you when not set)
+    owner: Option<Owner>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -54,6 +56,9 @@ pub struct Savings<T: std::cmp::Ord> {
     /// Calculated date values as a year based on input values
     #[serde(skip)]
     dates: Dates,
+    /// Percent interest earned in each year used instead of yearly_return (set for Monte Carlo runs)
+    #[serde(skip)]
+    return_sequence: Option<Table<u32>>,
 }
 
 impl From<Savings<String>> for Savings<u32> {
@@ -74,9 +79,11 @@ impl From<Savings<String>> for Savings<u32> {
             withdrawal_type: other.withdrawal_type,
             withdrawal_value: other.withdrawal_value,
             tax_status: other.tax_status,
+            owner: other.owner,
             notes: other.notes,
             analysis: other.analysis,
             dates: other.dates,
+            return_sequence: other.return_sequence,
         }
     }
 }
@@ -88,6 +95,9 @@ impl Account for Savings<u32> {
     fn link_id(&self) -> Option<String> {
         None
     }
+    fn owner(&self) -> Owner {
+        self.owner.unwrap_or_default()
+    }
     fn name(&self) -> String {
         self.name.clone()
     }
@@ -130,6 +140,9 @@ impl Account for Savings<u32> {
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    fn balance_sheet_value(&self, year: u32) -> Option<f64> {
+        self.get_value(year)
+    }
     fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
         Some(YearRange {
             start: self
@@ -183,9 +196,13 @@ impl Account for Savings<u32> {
     fn get_plot_data(&self) -> Vec<PlotDataSet> {
         self.analysis.get_plot_data()
     }
+    fn set_return_sequence(&mut self, returns: Table<u32>) {
+        self.return_sequence = Some(returns);
+    }
     fn simulate(
         &mut self,
         year: u32,
+        _linked_value: Option<f64>,
         totals: &YearlyTotals,
         settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
@@ -198,7 +215,7 @@ impl Account for Savings<u32> {
 
         // Calculate earnings
         result.earning =
-            self.analysis.value.get(year).unwrap() * (self.yearly_return.value(settings) / 100.0); // calculate earnings from interest
+            self.analysis.value.get(year).unwrap() * (self.get_yearly_return(year, settings) / 100.0); // calculate earnings from interest
 
         // Add earnings to earnings table & increase account value by earnings
         self.analysis.earnings.update(year, result.earning);
@@ -214,6 +231,7 @@ impl Account for Savings<u32> {
             .contributions
             .update(year, result.contribution);
         self.analysis.value.update(year, result.contribution);
+        self.analysis.add_cost_basis(year, result.contribution);
 
         // Calculate withdrawal
         if self.dates.year_out.unwrap().contains(year) {
@@ -221,17 +239,66 @@ impl Account for Savings<u32> {
         }
 
         // Add withdrawal to withdrawal table and subtract from value tables
+        let withdrawal_gain = self.analysis.withdraw_cost_basis(year, result.withdrawal);
         self.analysis.withdrawals.update(year, result.withdrawal);
         self.analysis.value.update(year, -result.withdrawal);
 
+        // Earnings are taxed as capital gains when earned unless taxes are deferred until withdrawal
+        let capital_gains = match self.tax_status {
+            TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => withdrawal_gain,
+            _ => result.earning,
+        };
+
         Ok(YearlyImpact {
             expense: result.contribution,
             healthcare_expense: 0_f64,
             col: 0_f64,
             saving: result.contribution + result.earning - result.withdrawal, // delta to savings total for the year
-            income_taxable: result.earning,
+            income_taxable: 0_f64,
+            capital_gains,
             income: result.withdrawal,
             hsa: 0_f64,
+            penalty: 0_f64,
+        })
+    }
+    fn tax_status(&self) -> Option<TaxStatus> {
+        Some(self.tax_status)
+    }
+    fn withdrawal_type(&self) -> Option<WithdrawalOptions> {
+        Some(self.withdrawal_type)
+    }
+    fn contribution_type(&self) -> Option<ContributionOptions> {
+        Some(self.contribution_type)
+    }
+    fn deposit(
+        &mut self,
+        year: u32,
+        amount: f64,
+        limit: Option<f64>,
+    ) -> Result<YearlyImpact, Box<dyn Error>> {
+        let amount = self.analysis.limit_contribution(year, amount, limit);
+        // Swept cash has already been taxed so it is added to the cost basis
+        self.analysis.contributions.update(year, amount);
+        self.analysis.value.update(year, amount);
+        self.analysis.add_cost_basis(year, amount);
+        Ok(YearlyImpact {
+            saving: amount,
+            ..Default::default()
+        })
+    }
+    fn withdraw(&mut self, year: u32, amount: f64) -> Result<YearlyImpact, Box<dyn Error>> {
+        let withdrawal = amount.min(self.analysis.value.get(year).unwrap_or_default()).max(0_f64);
+        let withdrawal_gain = self.analysis.withdraw_cost_basis(year, withdrawal);
+        self.analysis.withdrawals.update(year, withdrawal);
+        self.analysis.value.update(year, -withdrawal);
+        Ok(YearlyImpact {
+            saving: -withdrawal,
+            capital_gains: match self.tax_status {
+                TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => withdrawal_gain,
+                _ => 0_f64,
+            },
+            income: withdrawal,
+            ..Default::default()
         })
     }
     fn write(&self, filepath: String) {
@@ -268,6 +335,15 @@ mod tests {
                     high: 80_f64,
                 },
             },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
         }
     }
 
@@ -278,9 +354,11 @@ mod tests {
             table: Table::default(),
             start_out: YearInput::ConstantInt(2000),
             end_out: YearInput::ConstantInt(2020),
+            owner: None,
             notes: None,
             analysis: SavingsTables::default(),
             dates: Dates::default(),
+            return_sequence: None,
             contributions: Some(Table::default()),
             earnings: Some(Table::default()),
             withdrawals: Some(Table::default()),
@@ -445,6 +523,41 @@ mod tests {
 
     }
 
+    /// Tests that earnings are reported as capital gains instead of taxable income
+    #[test]
+    fn earnings_capital_gains() {
+        let settings = test_settings_values();
+        let year = 2010_u32;
+        let yearly_totals = YearlyTotals::new();
+
+        let mut account = test_account();
+        account.table = Table::from((vec![year - 1], vec![1_000_f64]));
+        account.init(None, &settings).unwrap();
+
+        let update = account.simulate(year, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.capital_gains, 200_f64, epsilon=0.001);
+        assert_approx_eq!(f64, update.income_taxable, 0_f64, epsilon=0.001);
+    }
+
+    /// Tests that only the gain share of a withdrawal is taxed when taxes are deferred until withdrawal
+    #[test]
+    fn withdrawal_capital_gains() {
+        let settings = test_settings_values();
+        let year = 2010_u32;
+        let yearly_totals = YearlyTotals::new();
+
+        let mut account = test_account();
+        account.tax_status = TaxStatus::ContributeTaxedEarningsTaxedWhenUsed;
+        account.table = Table::from((vec![year - 1], vec![1_000_f64]));
+        account.init(None, &settings).unwrap();
+
+        // balance of 1,700 (1,000 + 200 earnings + 500 contribution) with a cost basis of 1,500
+        let update = account.simulate(year, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.capital_gains, 100_f64 * 200_f64 / 1_700_f64, epsilon=0.001);
+        assert_approx_eq!(f64, update.income_taxable, 0_f64, epsilon=0.001);
+        assert_approx_eq!(f64, account.analysis.cost_basis.get(year).unwrap(), 1_500_f64 - 100_f64 * 1_500_f64 / 1_700_f64, epsilon=0.001);
+    }
+
     /// Tests get_withdrawal when type is WithdrawalOptions::Other
     #[test]
     fn withdrawal_other() {
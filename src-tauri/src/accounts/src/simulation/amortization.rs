@@ -0,0 +1,235 @@
+//! Month by month loan amortization schedule
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use ts_rs::TS;
+
+/// Balance below which a loan is considered paid off (to account for floating point math rounding)
+const PAID_OFF: f64 = 0.005;
+
+/// One month of an amortization schedule
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq)]
+pub struct AmortizationRow {
+    /// Calendar year of the payment
+    pub year: u32,
+    /// Month of the payment (1-12)
+    pub month: u32,
+    /// Scheduled principal and interest payment
+    pub payment: f64,
+    /// Extra amount paid toward the principal
+    pub extra_principal: f64,
+    /// Interest accrued for the month
+    pub interest: f64,
+    /// Amount of the payment that reduced the balance
+    pub principal: f64,
+    /// Balance after the payment
+    pub balance: f64,
+}
+
+/// Payoff date and interest totals of an amortization schedule
+#[derive(TS, Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AmortizationSummary {
+    /// Calendar year of the last payment
+    pub payoff_year: u32,
+    /// Month of the last payment (1-12)
+    pub payoff_month: u32,
+    /// Interest paid over the life of the loan
+    pub total_interest: f64,
+    /// Interest that the extra principal payments avoid
+    pub interest_saved: f64,
+}
+
+/// Payment that pays off `balance` in `periods` equal payments at `rate_per_period` (percent)
+///
+/// The rate must be for the same period as the payments (a monthly rate for a number of months
+/// or a yearly rate for a number of years).
+pub fn level_payment(balance: f64, rate_per_period: f64, periods: u32) -> f64 {
+    if periods == 0 {
+        return balance;
+    }
+    let rate = rate_per_period / 100_f64;
+    match rate > 0_f64 {
+        true => balance * rate / (1_f64 - (1_f64 + rate).powi(-(periods as i32))),
+        false => balance / periods as f64,
+    }
+}
+
+/// Monthly interest rate (percent) that is equivalent to an APR compounded `compound_time` times a year
+pub fn monthly_rate(apr: f64, compound_time: f64) -> f64 {
+    ((1_f64 + apr / 100_f64 / compound_time).powf(compound_time / 12_f64) - 1_f64) * 100_f64
+}
+
+/// Build the schedule for paying off `balance` over `months` starting in January of `start_year`
+///
+/// The scheduled payment is recomputed over the months left whenever the rate changes so a
+/// loan with a variable rate is still paid off in `months`.  Extra principal is paid on top of
+/// the scheduled payment and shortens the loan.
+pub fn amortize<F>(
+    balance: f64,
+    months: u32,
+    start_year: u32,
+    extra_principal: f64,
+    rate_in: F,
+) -> Vec<AmortizationRow>
+where
+    F: Fn(u32) -> f64,
+{
+    let mut rows: Vec<AmortizationRow> = Vec::new();
+    let mut balance = balance;
+    let mut rate: Option<f64> = None;
+    let mut payment = 0_f64;
+
+    for idx in 0..months {
+        if balance < PAID_OFF {
+            break;
+        }
+        let year = start_year + idx / 12;
+        let month_rate = rate_in(year);
+        if rate != Some(month_rate) {
+            payment = level_payment(balance, month_rate, months - idx);
+            rate = Some(month_rate);
+        }
+
+        let interest = balance * month_rate / 100_f64;
+        let scheduled = payment.min(balance + interest);
+        let extra = extra_principal
+            .min(balance + interest - scheduled)
+            .max(0_f64);
+        balance += interest - scheduled - extra;
+        if balance < PAID_OFF {
+            balance = 0_f64;
+        }
+        rows.push(AmortizationRow {
+            year,
+            month: idx % 12 + 1,
+            payment: scheduled,
+            extra_principal: extra,
+            interest,
+            principal: scheduled + extra - interest,
+            balance,
+        });
+    }
+    rows
+}
+
+/// Summarize a schedule compared to the same loan without extra principal payments
+///
+/// Returns None if the schedule does not pay off the loan.
+pub fn summarize(
+    schedule: &[AmortizationRow],
+    baseline: &[AmortizationRow],
+) -> Option<AmortizationSummary> {
+    let last = schedule.last().filter(|row| row.balance == 0_f64)?;
+    let total_interest: f64 = schedule.iter().map(|row| row.interest).sum();
+    let baseline_interest: f64 = baseline.iter().map(|row| row.interest).sum();
+    Some(AmortizationSummary {
+        payoff_year: last.year,
+        payoff_month: last.month,
+        total_interest,
+        interest_saved: baseline_interest - total_interest,
+    })
+}
+
+/// Write the schedule out to csv file (the summary is written as comment lines at the top)
+pub fn write_schedule(
+    schedule: &[AmortizationRow],
+    summary: Option<AmortizationSummary>,
+    filename: String,
+) {
+    let mut file = std::fs::File::create(filename).unwrap();
+    if let Some(summary) = summary {
+        file.write_all(
+            format!(
+                "# payoff: {}-{:02}, total interest: {:.2}, interest saved: {:.2}\n",
+                summary.payoff_year,
+                summary.payoff_month,
+                summary.total_interest,
+                summary.interest_saved
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    }
+    file.write_all(
+        "year, month, payment, extra_principal, interest, principal, balance\n".as_bytes(),
+    )
+    .unwrap();
+    schedule.iter().for_each(|row| {
+        file.write_all(
+            format!(
+                "{}, {}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}\n",
+                row.year,
+                row.month,
+                row.payment,
+                row.extra_principal,
+                row.interest,
+                row.principal,
+                row.balance,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+
+    #[test]
+    fn payment() {
+        assert_approx_eq!(
+            f64,
+            level_payment(100_000_f64, 0.5, 360),
+            599.55,
+            epsilon = 0.01
+        );
+        assert_approx_eq!(f64, level_payment(1_200_f64, 0_f64, 12), 100_f64);
+        assert_approx_eq!(f64, monthly_rate(6_f64, 12_f64), 0.5, epsilon = 1e-9);
+        // yearly compounding is a lower effective monthly rate
+        assert!(monthly_rate(6_f64, 1_f64) < 0.5);
+    }
+
+    #[test]
+    fn schedule() {
+        let schedule = amortize(100_000_f64, 360, 2000, 0_f64, |_| 0.5);
+        assert_eq!(schedule.len(), 360);
+        assert_approx_eq!(f64, schedule[0].interest, 500_f64);
+        assert_approx_eq!(f64, schedule[0].principal, 99.55, epsilon = 0.01);
+        assert_eq!((schedule[12].year, schedule[12].month), (2001, 1));
+        assert_approx_eq!(f64, schedule.last().unwrap().balance, 0_f64);
+
+        let summary = summarize(&schedule, &schedule).unwrap();
+        assert_eq!((summary.payoff_year, summary.payoff_month), (2029, 12));
+        assert_approx_eq!(f64, summary.total_interest, 115_838.19, epsilon = 0.5);
+        assert_approx_eq!(f64, summary.interest_saved, 0_f64);
+    }
+
+    #[test]
+    fn schedule_extra_principal() {
+        let baseline = amortize(100_000_f64, 360, 2000, 0_f64, |_| 0.5);
+        let schedule = amortize(100_000_f64, 360, 2000, 100_f64, |_| 0.5);
+        assert!(schedule.len() < baseline.len());
+        let summary = summarize(&schedule, &baseline).unwrap();
+        assert!(summary.payoff_year < 2029);
+        assert!(summary.interest_saved > 0_f64);
+
+        // a schedule that does not reach zero has no payoff date
+        assert_eq!(summarize(&schedule[..12], &baseline), None);
+    }
+
+    #[test]
+    fn schedule_rate_change() {
+        // the payment is recomputed so the loan is still paid off on time
+        let schedule = amortize(100_000_f64, 360, 2000, 0_f64, |year| match year < 2005 {
+            true => 0.5,
+            false => 0.75,
+        });
+        assert_eq!(schedule.len(), 360);
+        assert!(schedule[60].payment > schedule[59].payment);
+        assert_approx_eq!(f64, schedule.last().unwrap().balance, 0_f64);
+    }
+}
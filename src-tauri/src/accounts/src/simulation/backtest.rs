@@ -0,0 +1,313 @@
+//! Replay a plan against historical market returns and inflation
+//!
+//! Every year in the bundled dataset with enough history after it to cover the whole plan is
+//! used as a starting year.  The first simulated year gets the return and inflation of the
+//! starting year, the second year gets the values of the following year, and so on.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{run, SimulationError, Table};
+use crate::{Account, AccountWrapper, UserData};
+
+/// Bundled historical data (year, market return, inflation)
+const HISTORICAL_RETURNS: &str = include_str!("../../data/historical_returns.csv");
+
+/// Market history for a single year
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HistoricalYear {
+    /// Calendar year
+    pub year: u32,
+    /// Market return including dividends (percent)
+    pub yearly_return: f64,
+    /// Inflation rate (percent)
+    pub inflation: f64,
+}
+
+/// Parse the bundled historical dataset
+pub fn historical_data() -> Vec<HistoricalYear> {
+    HISTORICAL_RETURNS
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with("year"))
+        .filter_map(|line| {
+            let values: Vec<&str> = line.split(',').map(|v| v.trim()).collect();
+            match values.as_slice() {
+                [year, yearly_return, inflation] => Some(HistoricalYear {
+                    year: year.parse().ok()?,
+                    yearly_return: yearly_return.parse().ok()?,
+                    inflation: inflation.parse().ok()?,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Outcome of running the plan starting from one historical year
+#[derive(TS, Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestRun {
+    /// Historical year used for the first simulated year
+    pub start_year: u32,
+    /// Net never dropped below zero
+    pub success: bool,
+    /// The simulation stopped with an error (the run failed and has no ending values)
+    pub errored: bool,
+    /// Total savings in the last simulated year
+    pub ending_saving: f64,
+    /// Net cash in the last simulated year
+    pub ending_net: f64,
+}
+
+impl BacktestRun {
+    /// Total money left at the end of the plan
+    fn ending_total(&self) -> f64 {
+        self.ending_saving + self.ending_net
+    }
+}
+
+/// Results of replaying a plan against every historical starting year
+#[derive(TS, Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct BacktestResult {
+    /// Outcome for every starting year (in starting year order)
+    pub runs: Vec<BacktestRun>,
+    /// Starting year with the least money left at the end of the plan (runs that stopped
+    /// with an error rank below every run that completed)
+    pub worst: Option<BacktestRun>,
+    /// Starting year with the median amount of money left at the end of the plan
+    pub median: Option<BacktestRun>,
+    /// Starting year with the most money left at the end of the plan
+    pub best: Option<BacktestRun>,
+    /// Number of starting years where the plan failed (including runs that stopped with an error)
+    pub failures: u32,
+    /// Number of starting years where the simulation stopped with an error
+    pub errors: u32,
+}
+
+/// Replay the plan against each starting year in `history` that has enough data for the plan
+///
+/// The historical return replaces the yearly return of every account that earns a return
+/// and the historical inflation replaces the base rate of inflation.  A starting year that
+/// stops with an error while simulating a year has failed.  Errors setting up the plan are the
+/// same for every starting year so they are returned instead.
+pub fn run_backtest_with(
+    data: &UserData<AccountWrapper>,
+    history: &[HistoricalYear],
+) -> Result<BacktestResult, SimulationError> {
+    let years: Vec<u32> = (data.settings.year_start()..data.settings.year_end()).collect();
+    let mut runs: Vec<BacktestRun> = Vec::new();
+
+    // Starting years that would run out of history before the plan ends are skipped
+    for start in 0..(history.len() + 1).saturating_sub(years.len()) {
+        let sequence = &history[start..start + years.len()];
+        let returns: Table<u32> = (
+            years.clone(),
+            sequence.iter().map(|h| h.yearly_return).collect(),
+        )
+            .into();
+        let inflation: Table<u32> = (
+            years.clone(),
+            sequence.iter().map(|h| h.inflation).collect(),
+        )
+            .into();
+
+        let mut run_data: UserData<Box<dyn Account>> = data.clone().into();
+        run_data.settings.inflation_sequence = Some(inflation);
+        run_data
+            .accounts
+            .values_mut()
+            .for_each(|account| account.set_return_sequence(returns.clone()));
+
+        match run(run_data) {
+            Ok(result) => {
+                let last = years.last().copied().unwrap_or_default();
+                runs.push(BacktestRun {
+                    start_year: history[start].year,
+                    success: result.succeeded(),
+                    errored: false,
+                    ending_saving: result.totals.get_saving(last),
+                    ending_net: result.totals.net.get(last).unwrap_or_default(),
+                });
+            }
+            // Returns and inflation can make a plan run out of money in ways that stop the
+            // simulation
+            Err(SimulationError::Simulate { .. }) => runs.push(BacktestRun {
+                start_year: history[start].year,
+                errored: true,
+                ..Default::default()
+            }),
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut ranked = runs.clone();
+    ranked.sort_by(|a, b| {
+        b.errored
+            .cmp(&a.errored)
+            .then(a.ending_total().total_cmp(&b.ending_total()))
+    });
+
+    Ok(BacktestResult {
+        worst: ranked.first().copied(),
+        median: ranked.get(ranked.len() / 2).copied(),
+        best: ranked.last().copied(),
+        failures: runs.iter().filter(|run| !run.success).count() as u32,
+        errors: runs.iter().filter(|run| run.errored).count() as u32,
+        runs,
+    })
+}
+
+/// Replay the plan against every starting year in the bundled historical dataset
+pub fn run_backtest(data: &UserData<AccountWrapper>) -> Result<BacktestResult, SimulationError> {
+    run_backtest_with(data, &historical_data())
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+
+    /// Plan with one year of income and a savings account that pays for expenses after that
+    fn test_user_data() -> UserData<AccountWrapper> {
+        let json = r#"{
+            "settings": {
+                "ageRetire": 50,
+                "ageDie": 23,
+                "yearBorn": 1980,
+                "yearStart": 2000,
+                "inflationBase": 0.0,
+                "taxIncome": 0.0,
+                "taxCapitalGains": 0.0,
+                "retirementCostOfLiving": 100.0,
+                "ssa": {
+                    "breakpoints": {"low": 30000, "high": 40000},
+                    "taxableIncomePercentage": {"low": 50, "high": 80}
+                },
+                "cashSweep": {
+                    "cashBuffer": 0,
+                    "depositAccounts": ["savings"],
+                    "withdrawalOrder": ["savings"]
+                }
+            },
+            "accounts": {
+                "income": {
+                    "type": "income",
+                    "name": "Job",
+                    "table": {},
+                    "base": 1000,
+                    "startIn": 2000,
+                    "endIn": 2000,
+                    "raise": 0
+                },
+                "expense": {
+                    "type": "expense",
+                    "name": "Food",
+                    "table": {},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "expenseType": "fixed_with_inflation",
+                    "expenseValue": 400,
+                    "isHealthcare": false
+                },
+                "savings": {
+                    "type": "savings",
+                    "name": "Savings",
+                    "table": {},
+                    "startIn": 2000,
+                    "endIn": 2010,
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "contributionValue": 0,
+                    "contributionType": "fixed",
+                    "yearlyReturn": 0,
+                    "withdrawalType": "other",
+                    "withdrawalValue": 0,
+                    "taxStatus": "contribute_taxed_earnings_untaxed_when_used"
+                }
+            }
+        }"#;
+        serde_json::from_str::<UserData<AccountWrapper>>(json).unwrap()
+    }
+
+    #[test]
+    fn bundled_dataset() {
+        let history = historical_data();
+        assert_eq!(history.first().unwrap().year, 1928);
+        assert_eq!(
+            history.len() as u32,
+            history.last().unwrap().year - 1928 + 1
+        );
+    }
+
+    #[test]
+    fn backtest() {
+        let history = vec![
+            HistoricalYear {
+                year: 1,
+                yearly_return: 50_f64,
+                inflation: 0_f64,
+            },
+            HistoricalYear {
+                year: 2,
+                yearly_return: 0_f64,
+                inflation: 50_f64,
+            },
+            HistoricalYear {
+                year: 3,
+                yearly_return: 100_f64,
+                inflation: 0_f64,
+            },
+            HistoricalYear {
+                year: 4,
+                yearly_return: 50_f64,
+                inflation: 0_f64,
+            },
+            HistoricalYear {
+                year: 5,
+                yearly_return: 0_f64,
+                inflation: 50_f64,
+            },
+        ];
+        let result = run_backtest_with(&test_user_data(), &history).unwrap();
+        // only years 1 to 3 have enough history for the three years of the plan
+        assert_eq!(result.runs.len(), 3);
+        assert_eq!(result.runs[0].start_year, 1);
+        assert_eq!(result.runs[2].start_year, 3);
+
+        // starting in year 1 the 600 saved in 2000 earns nothing in 2001 and expenses rise
+        // to 600 in 2002 so the savings run out
+        assert!(!result.runs[0].success);
+        // starting in year 2 the savings double in 2001 and grow 50% in 2002 while expenses are 600
+        assert!(result.runs[1].success);
+        assert_approx_eq!(
+            f64,
+            result.runs[1].ending_saving,
+            (600_f64 * 2_f64 - 600_f64) * 1.5 - 600_f64
+        );
+
+        assert_eq!(result.worst.unwrap().start_year, 1);
+        assert_eq!(result.median.unwrap().start_year, 3);
+        assert_eq!(result.best.unwrap().start_year, 2);
+        assert_eq!(result.failures, 1);
+        assert_eq!(result.errors, 0);
+
+        // covering the deficit from the job errors when the savings run out
+        let mut data = test_user_data();
+        data.settings
+            .cash_sweep
+            .as_mut()
+            .unwrap()
+            .withdrawal_order
+            .push("income".into());
+        let result = run_backtest_with(&data, &history).unwrap();
+        assert_eq!(result.runs.len(), 3);
+        assert!(result.runs[0].errored);
+        assert!(!result.runs[0].success);
+        assert_eq!(result.worst.unwrap().start_year, 1);
+        assert_eq!(result.best.unwrap().start_year, 2);
+        assert_eq!(result.failures, 1);
+        assert_eq!(result.errors, 1);
+    }
+}
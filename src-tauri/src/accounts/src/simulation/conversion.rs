@@ -0,0 +1,137 @@
+//! Compare a plan with and without its Roth conversions
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{run, SimulationError};
+use crate::{Account, AccountWrapper, UserData};
+
+/// Lifetime taxes and ending savings of a plan with and without its Roth conversions
+#[derive(TS, Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionComparison {
+    /// Income and capital gains tax paid over every year with the conversions
+    pub lifetime_taxes: f64,
+    /// Income and capital gains tax paid over every year without the conversions
+    pub lifetime_taxes_without_conversions: f64,
+    /// Total savings in the last simulated year with the conversions
+    pub ending_saving: f64,
+    /// Total savings in the last simulated year without the conversions
+    pub ending_saving_without_conversions: f64,
+}
+
+/// Run the plan as entered and again with the Roth conversions removed
+pub fn run_conversion_comparison(
+    data: &UserData<AccountWrapper>,
+) -> Result<ConversionComparison, SimulationError> {
+    let with: UserData<Box<dyn Account>> = data.clone().into();
+    let mut without: UserData<Box<dyn Account>> = data.clone().into();
+    without.settings.roth_conversion = None;
+
+    let with = run(with)?;
+    let without = run(without)?;
+    let last = data.settings.year_end() - 1;
+
+    Ok(ConversionComparison {
+        lifetime_taxes: with.totals.lifetime_taxes(),
+        lifetime_taxes_without_conversions: without.totals.lifetime_taxes(),
+        ending_saving: with.totals.get_saving(last),
+        ending_saving_without_conversions: without.totals.get_saving(last),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+
+    /// Plan with a job, a pretax account, and a Roth account that converts 2000 a year for two years
+    fn test_user_data() -> UserData<AccountWrapper> {
+        let json = r#"{
+            "settings": {
+                "ageRetire": 50,
+                "ageDie": 25,
+                "yearBorn": 1980,
+                "yearStart": 2000,
+                "inflationBase": 0.0,
+                "taxIncome": 20.0,
+                "taxCapitalGains": 0.0,
+                "retirementCostOfLiving": 100.0,
+                "ssa": {
+                    "breakpoints": {"low": 30000, "high": 40000},
+                    "taxableIncomePercentage": {"low": 50, "high": 80}
+                },
+                "rothConversion": {
+                    "fromAccount": "ira",
+                    "toAccount": "roth",
+                    "startYear": 2000,
+                    "endYear": 2001,
+                    "conversionType": "fixed",
+                    "conversionValue": 2000
+                }
+            },
+            "accounts": {
+                "income": {
+                    "type": "income",
+                    "name": "Job",
+                    "table": {},
+                    "base": 1000,
+                    "startIn": 2000,
+                    "endIn": 2010,
+                    "raise": 0
+                },
+                "ira": {
+                    "type": "retirement",
+                    "name": "IRA",
+                    "table": {"1999": 10000},
+                    "startIn": 2000,
+                    "endIn": 2010,
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "contributionValue": 0,
+                    "contributionType": "fixed",
+                    "yearlyReturn": 0,
+                    "withdrawalType": "other",
+                    "withdrawalValue": 0,
+                    "taxStatus": "contribute_pretax_taxed_when_used"
+                },
+                "roth": {
+                    "type": "retirement",
+                    "name": "Roth",
+                    "table": {},
+                    "startIn": 2000,
+                    "endIn": 2010,
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "contributionValue": 0,
+                    "contributionType": "fixed",
+                    "yearlyReturn": 0,
+                    "withdrawalType": "other",
+                    "withdrawalValue": 0,
+                    "taxStatus": "contribute_taxed_earnings_untaxed_when_used"
+                }
+            }
+        }"#;
+        serde_json::from_str::<UserData<AccountWrapper>>(json).unwrap()
+    }
+
+    #[test]
+    fn conversion_comparison() {
+        let comparison = run_conversion_comparison(&test_user_data()).unwrap();
+        // 5 years of 20% tax on 1000 of income plus 20% on the 4000 converted
+        assert_approx_eq!(
+            f64,
+            comparison.lifetime_taxes_without_conversions,
+            1_000_f64
+        );
+        assert_approx_eq!(f64, comparison.lifetime_taxes, 1_800_f64);
+        // conversions move money between accounts without changing total savings
+        assert_approx_eq!(f64, comparison.ending_saving, 10_000_f64);
+        assert_approx_eq!(
+            f64,
+            comparison.ending_saving_without_conversions,
+            10_000_f64
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Household level drawdown planner
+//!
+//! Decides how much to withdraw from each account that uses planned withdrawals so that the
+//! cost of living is covered according to the selected [DrawdownStrategy].
+
+use crate::inputs::{DrawdownStrategy, TaxStatus};
+
+/// Account that takes part in the drawdown plan for a year
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawdownAccount {
+    /// Id of the account
+    pub uuid: String,
+    /// Money available in the account this year
+    pub balance: f64,
+    /// How cashflow in this account is treated for tax purposes
+    pub tax_status: TaxStatus,
+}
+
+/// How a withdrawal from an account is taxed (in the order the accounts are drawn down taxable first)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum TaxTreatment {
+    /// Earnings are taxed as capital gains
+    Taxable,
+    /// Withdrawals are taxed as income
+    TaxDeferred,
+    /// Withdrawals are not taxed
+    TaxFree,
+}
+
+impl From<TaxStatus> for TaxTreatment {
+    fn from(tax_status: TaxStatus) -> Self {
+        match tax_status {
+            TaxStatus::ContributeTaxedEarningsTaxed => TaxTreatment::Taxable,
+            TaxStatus::ContributeTaxedEarningsTaxedWhenUsed => TaxTreatment::Taxable,
+            TaxStatus::ContributePretaxTaxedWhenUsed => TaxTreatment::TaxDeferred,
+            TaxStatus::ContributeTaxedEarningsUntaxedWhenUsed => TaxTreatment::TaxFree,
+            TaxStatus::ContributePretaxUntaxedWhenUsed => TaxTreatment::TaxFree,
+        }
+    }
+}
+
+/// Decide how much to withdraw from each account (returned in the same order as `accounts`)
+///
+///  need: amount of spending to cover for the year
+///  tax_rate: marginal income tax rate (percent), tax deferred withdrawals are increased to cover the tax on them
+///  bracket_room: taxable income left before the top of the lowest tax bracket
+pub fn plan_drawdown(
+    strategy: DrawdownStrategy,
+    need: f64,
+    accounts: &[DrawdownAccount],
+    tax_rate: f64,
+    bracket_room: f64,
+) -> Vec<f64> {
+    // fraction of each dollar withdrawn that is left to spend after income tax
+    let after_tax: Vec<f64> = accounts
+        .iter()
+        .map(|account| match TaxTreatment::from(account.tax_status) {
+            TaxTreatment::TaxDeferred => 1_f64 - tax_rate / 100_f64,
+            _ => 1_f64,
+        })
+        .collect();
+    let mut withdrawals = vec![0_f64; accounts.len()];
+    let mut remaining = need;
+
+    match strategy {
+        DrawdownStrategy::Proportional => {
+            let total: f64 = accounts.iter().map(|a| a.balance.max(0_f64)).sum();
+            if total > 0_f64 {
+                for (idx, account) in accounts.iter().enumerate() {
+                    let balance = account.balance.max(0_f64);
+                    withdrawals[idx] = (need * balance / total / after_tax[idx]).min(balance);
+                    remaining -= withdrawals[idx] * after_tax[idx];
+                }
+            }
+            // Accounts that ran dry leave part of the need uncovered so the others cover the rest
+            take_in_order(accounts, &after_tax, &mut withdrawals, &mut remaining);
+        }
+        DrawdownStrategy::TaxableFirst => {
+            take_in_order(accounts, &after_tax, &mut withdrawals, &mut remaining);
+        }
+        DrawdownStrategy::FillLowBracket => {
+            let mut room = bracket_room.max(0_f64);
+            for (idx, account) in accounts.iter().enumerate() {
+                if TaxTreatment::from(account.tax_status) != TaxTreatment::TaxDeferred {
+                    continue;
+                }
+                let withdrawal = (remaining / after_tax[idx])
+                    .min(account.balance)
+                    .min(room)
+                    .max(0_f64);
+                withdrawals[idx] += withdrawal;
+                room -= withdrawal;
+                remaining -= withdrawal * after_tax[idx];
+            }
+            take_in_order(accounts, &after_tax, &mut withdrawals, &mut remaining);
+        }
+    }
+    withdrawals
+}
+
+/// Cover the remaining need taking from taxable, then tax deferred, then tax free accounts
+fn take_in_order(
+    accounts: &[DrawdownAccount],
+    after_tax: &[f64],
+    withdrawals: &mut [f64],
+    remaining: &mut f64,
+) {
+    let mut order: Vec<usize> = (0..accounts.len()).collect();
+    order.sort_by_key(|idx| TaxTreatment::from(accounts[*idx].tax_status));
+
+    for idx in order {
+        if *remaining <= 0_f64 {
+            break;
+        }
+        let available = accounts[idx].balance - withdrawals[idx];
+        let withdrawal = (*remaining / after_tax[idx]).min(available).max(0_f64);
+        withdrawals[idx] += withdrawal;
+        *remaining -= withdrawal * after_tax[idx];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+
+    /// Taxable, tax deferred, and tax free accounts with 1000 in each
+    fn test_accounts() -> Vec<DrawdownAccount> {
+        vec![
+            DrawdownAccount {
+                uuid: "roth".into(),
+                balance: 1_000_f64,
+                tax_status: TaxStatus::ContributeTaxedEarningsUntaxedWhenUsed,
+            },
+            DrawdownAccount {
+                uuid: "ira".into(),
+                balance: 1_000_f64,
+                tax_status: TaxStatus::ContributePretaxTaxedWhenUsed,
+            },
+            DrawdownAccount {
+                uuid: "brokerage".into(),
+                balance: 1_000_f64,
+                tax_status: TaxStatus::ContributeTaxedEarningsTaxedWhenUsed,
+            },
+        ]
+    }
+
+    #[test]
+    fn taxable_first() {
+        let withdrawals = plan_drawdown(
+            DrawdownStrategy::TaxableFirst,
+            1_800_f64,
+            &test_accounts(),
+            20_f64,
+            0_f64,
+        );
+        // brokerage is emptied then the ira covers the last 800 plus tax
+        assert_approx_eq!(f64, withdrawals[2], 1_000_f64);
+        assert_approx_eq!(f64, withdrawals[1], 1_000_f64);
+        assert_approx_eq!(f64, withdrawals[0], 0_f64);
+
+        let withdrawals = plan_drawdown(
+            DrawdownStrategy::TaxableFirst,
+            2_000_f64,
+            &test_accounts(),
+            20_f64,
+            0_f64,
+        );
+        // the ira only covers 800 after tax so the roth covers the rest
+        assert_approx_eq!(f64, withdrawals[0], 200_f64);
+    }
+
+    #[test]
+    fn proportional() {
+        let withdrawals = plan_drawdown(
+            DrawdownStrategy::Proportional,
+            600_f64,
+            &test_accounts(),
+            20_f64,
+            0_f64,
+        );
+        assert_approx_eq!(f64, withdrawals[0], 200_f64);
+        assert_approx_eq!(f64, withdrawals[1], 250_f64);
+        assert_approx_eq!(f64, withdrawals[2], 200_f64);
+    }
+
+    #[test]
+    fn proportional_account_runs_dry() {
+        let withdrawals = plan_drawdown(
+            DrawdownStrategy::Proportional,
+            2_500_f64,
+            &test_accounts(),
+            20_f64,
+            0_f64,
+        );
+        // the ira share plus tax is more than its balance so the brokerage covers the 33.33 left
+        assert_approx_eq!(f64, withdrawals[1], 1_000_f64);
+        assert_approx_eq!(f64, withdrawals[0], 2_500_f64 / 3_f64, epsilon = 1e-9);
+        assert_approx_eq!(f64, withdrawals[2], 2_600_f64 / 3_f64, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn fill_low_bracket() {
+        let withdrawals = plan_drawdown(
+            DrawdownStrategy::FillLowBracket,
+            1_000_f64,
+            &test_accounts(),
+            20_f64,
+            500_f64,
+        );
+        // 500 of the ira fills the bracket (400 after tax) and the brokerage covers the rest
+        assert_approx_eq!(f64, withdrawals[1], 500_f64);
+        assert_approx_eq!(f64, withdrawals[2], 600_f64);
+        assert_approx_eq!(f64, withdrawals[0], 0_f64);
+    }
+}
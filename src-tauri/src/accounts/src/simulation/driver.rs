@@ -0,0 +1,1589 @@
+//! Run the simulation across all accounts in a user data set
+
+use log::{info, trace};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+use super::{
+    plan_drawdown, AmortizationSummary, Dates, DrawdownAccount, PlotDataSet, SimulationError,
+    YearlyImpact, YearlyTotals,
+};
+use crate::inputs::{
+    fixed_with_inflation, ContributionOptions, Owner, TaxStatus, WithdrawalOptions,
+};
+use crate::{Account, AccountType, AccountWrapper, UserData};
+
+/// Maximum number of passes through the withdrawal order when covering a deficit
+///
+/// Withdrawals can be taxable which creates a new (smaller) deficit, so a few passes are
+/// needed for net to settle.
+const MAX_SWEEP_PASSES: usize = 10;
+
+/// Simulation of a full set of user data
+///
+/// Holds the accounts being simulated along with the order they are processed in and
+/// the years the simulation spans.
+#[derive(Debug)]
+pub struct Simulation {
+    /// User settings and the accounts to simulate
+    data: UserData<Box<dyn Account>>,
+    /// Account uuids in the order they must be processed
+    account_order: Vec<String>,
+    /// Years to run the simulation for
+    years: Vec<u32>,
+}
+
+/// Results of running a [Simulation]
+#[derive(Debug)]
+pub struct SimulationResult {
+    /// User settings and the simulated accounts (each account holds its own analysis tables)
+    pub data: UserData<Box<dyn Account>>,
+    /// Account uuids in the order they were processed
+    pub account_order: Vec<String>,
+    /// Years the simulation was run for
+    pub years: Vec<u32>,
+    /// Totals across all accounts for each year
+    pub totals: YearlyTotals,
+}
+
+/// Results of a [Simulation] in the form sent to the UI
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisResults {
+    /// Analysis data for every account (keyed by uuid) to use in UI plotting
+    pub plot_data: HashMap<String, Vec<PlotDataSet>>,
+    /// Totals across all accounts for each year
+    pub totals: YearlyTotals,
+    /// Years that early withdrawal penalties were paid in
+    pub penalty_years: Vec<u32>,
+    /// Payoff date and interest totals of each account with an amortization schedule (by uuid)
+    pub amortization: HashMap<String, AmortizationSummary>,
+}
+
+impl Simulation {
+    /// Create a new simulation for the user data
+    pub fn new(data: UserData<Box<dyn Account>>) -> Simulation {
+        // Loop through accounts to determine what order they should be processed in
+        let mut account_order: Vec<String> = Vec::new();
+        for type_id in AccountWrapper::order().iter() {
+            for (uuid, account) in data.accounts.iter() {
+                if account.type_id() == *type_id {
+                    account_order.push(uuid.to_string());
+                }
+            }
+        }
+
+        // Initialize vector of year values
+        let years: Vec<u32> =
+            (data.settings.year_start()..data.settings.year_end()).collect::<Vec<u32>>();
+
+        Simulation {
+            data,
+            account_order,
+            years,
+        }
+    }
+
+    /// Run the simulation for every year and every account
+    ///
+    /// Stops at the first account that fails to initialize or simulate and returns
+    /// an error identifying that account.
+    pub fn run(mut self) -> Result<SimulationResult, SimulationError> {
+        // Initilize object to keep track of yearly totals across all accounts
+        let mut yearly_totals = YearlyTotals::new();
+
+        // Make sure every account the cash sweep refers to exists before simulating anything
+        if let Some(sweep) = &self.data.settings.cash_sweep {
+            for uuid in sweep
+                .deposit_accounts
+                .iter()
+                .chain(sweep.withdrawal_order.iter())
+            {
+                if !self.data.accounts.contains_key(uuid) {
+                    return Err(SimulationError::MissingAccount {
+                        uuid: uuid.to_string(),
+                    });
+                }
+            }
+            // Swept cash has already been taxed so it would be taxed again when withdrawn from a
+            // pretax account
+            for uuid in sweep.deposit_accounts.iter() {
+                let account = &self.data.accounts[uuid];
+                if account.tax_status() == Some(TaxStatus::ContributePretaxTaxedWhenUsed) {
+                    return Err(SimulationError::Init {
+                        uuid: uuid.to_string(),
+                        name: account.name(),
+                        cause: String::from("Cash can not be swept into a pretax account"),
+                    });
+                }
+            }
+        }
+
+        // Make sure every account an event moves money into or out of exists and is not pretax
+        // (event money would be taxed again when withdrawn from a pretax account)
+        for uuid in self.account_order.iter() {
+            let account = &self.data.accounts[uuid];
+            if let Some(destination) = account.destination() {
+                match self.data.accounts.get(&destination) {
+                    None => {
+                        return Err(SimulationError::MissingLink {
+                            uuid: uuid.to_string(),
+                            name: account.name(),
+                            link_id: destination,
+                        })
+                    }
+                    Some(linked)
+                        if linked.tax_status() == Some(TaxStatus::ContributePretaxTaxedWhenUsed) =>
+                    {
+                        return Err(SimulationError::Init {
+                            uuid: uuid.to_string(),
+                            name: account.name(),
+                            cause: String::from("Event destination can not be a pretax account"),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        // Make sure every account owned by a spouse has a spouse to belong to
+        if self.data.settings.spouse.is_none() {
+            for uuid in self.account_order.iter() {
+                let account = &self.data.accounts[uuid];
+                if account.owner() == Owner::Spouse {
+                    return Err(SimulationError::Init {
+                        uuid: uuid.to_string(),
+                        name: account.name(),
+                        cause: String::from("Account is owned by a spouse but no spouse is set"),
+                    });
+                }
+            }
+        }
+
+        // Make sure every account that contributes the maximum allowed has a contribution limit
+        for uuid in self.account_order.iter() {
+            let account = &self.data.accounts[uuid];
+            if account.contribution_type() == Some(ContributionOptions::MaxAllowed)
+                && account
+                    .contribution_limit(self.data.settings.year_start(), &self.data.settings)
+                    .is_none()
+            {
+                return Err(SimulationError::Init {
+                    uuid: uuid.to_string(),
+                    name: account.name(),
+                    cause: String::from(
+                        "Contributing the maximum allowed requires a contribution limit for the account",
+                    ),
+                });
+            }
+        }
+
+        // Make sure Roth conversions move money from a pretax retirement account to a different
+        // Roth retirement account (anything else would tax the converted money twice)
+        if let Some(conversion) = &self.data.settings.roth_conversion {
+            for uuid in [&conversion.from_account, &conversion.to_account] {
+                let account =
+                    self.data
+                        .accounts
+                        .get(uuid)
+                        .ok_or_else(|| SimulationError::MissingAccount {
+                            uuid: uuid.to_string(),
+                        })?;
+                let cause = if account.type_id() != AccountType::Retirement {
+                    Some("Roth conversions must be between retirement accounts")
+                } else if conversion.from_account == conversion.to_account {
+                    Some("Roth conversions must be between two different accounts")
+                } else if *uuid == conversion.from_account
+                    && account.tax_status() != Some(TaxStatus::ContributePretaxTaxedWhenUsed)
+                {
+                    Some("Roth conversions must be from a pretax account")
+                } else if *uuid == conversion.to_account
+                    && account.tax_status()
+                        != Some(TaxStatus::ContributeTaxedEarningsUntaxedWhenUsed)
+                {
+                    Some("Roth conversions must be to a Roth account")
+                } else {
+                    None
+                };
+                if let Some(cause) = cause {
+                    return Err(SimulationError::Init {
+                        uuid: uuid.to_string(),
+                        name: account.name(),
+                        cause: String::from(cause),
+                    });
+                }
+            }
+        }
+
+        // Initialize accounts
+        for uuid in self.account_order.iter() {
+            // Get dates from the linked account if this account has a link ID
+            let linked_dates = self.linked_dates(uuid)?;
+
+            // Initialize the account & get the impacts it has based on the tables of historical data the user has input
+            let account = self.data.accounts.get_mut(uuid).unwrap();
+            let impacts = account
+                .init(linked_dates, &self.data.settings)
+                .map_err(|e| SimulationError::Init {
+                    uuid: uuid.to_string(),
+                    name: account.name(),
+                    cause: e.to_string(),
+                })?;
+
+            // Apply the impacts to yearly totals
+            impacts.iter().for_each(|(year, impact)| {
+                if !yearly_totals.contains_year(*year) {
+                    yearly_totals.add_year(*year, false).unwrap();
+                }
+                yearly_totals.update(*year, *impact);
+            });
+
+            trace!(
+                "{:?} {:?} {:?}",
+                self.data.accounts[uuid].type_id(),
+                uuid,
+                self.data.accounts[uuid].name(),
+            );
+        }
+
+        info!("Main Loop");
+
+        // Main loop to loop through each year
+        for year in self.years.clone() {
+            // Add a new year to yearly_totals and pull some of the previous values forward
+            // If the year already exists (as it might if a user has historical data that
+            // conflicts with this analysis year) then skip analysis and leave the yearly total
+            // tables as they are.
+            if yearly_totals.add_year(year, true).is_ok() {
+                // Loop through accounts to make contributions and withdrawals
+                for uuid in self.account_order.iter() {
+                    // Get the value of the linked account for this year if this account has a link ID
+                    let linked_value = self.linked_value(uuid, year);
+                    // Simulate this year for the account with specified uuid
+                    let account = self.data.accounts.get_mut(uuid).unwrap();
+                    let impact = account
+                        .simulate(year, linked_value, &yearly_totals, &self.data.settings)
+                        .map_err(|e| SimulationError::Simulate {
+                            uuid: uuid.to_string(),
+                            name: account.name(),
+                            year,
+                            cause: e.to_string(),
+                        })?;
+                    // Apply the impact for this account to yearly_totals
+                    yearly_totals.update(year, impact);
+                    // Count the contribution toward the limit shared with the owner's accounts
+                    self.count_contribution(uuid, year, 0_f64, &mut yearly_totals);
+                }
+
+                // Move money from one time events into or out of their destination accounts
+                self.transfer_events(year, &mut yearly_totals)?;
+
+                // Make the withdrawals decided by the household drawdown planner
+                self.drawdown(year, &mut yearly_totals)?;
+
+                // Move money from the pretax account to the Roth account
+                self.convert(year, &mut yearly_totals)?;
+
+                // Close out the year
+                yearly_totals.deposit_income_in_net(year);
+                yearly_totals.pay_income_tax_from_net(year, &self.data.settings);
+                yearly_totals.pay_capital_gains_tax_from_net(year, &self.data.settings);
+                yearly_totals.pay_expenses_from_net(year);
+                yearly_totals.pay_penalties_from_net(year);
+                yearly_totals.pay_healthcare_expenses_from_net(year);
+                self.sweep_cash(year, &mut yearly_totals)?;
+                self.record_balance_sheet(year, &mut yearly_totals);
+            }
+        }
+
+        Ok(SimulationResult {
+            data: self.data,
+            account_order: self.account_order,
+            years: self.years,
+            totals: yearly_totals,
+        })
+    }
+
+    /// Record the balance sheet and home equity once every account is settled for the year
+    ///
+    /// Equity is the value of real estate less the balance of the mortgages linked to it.
+    fn record_balance_sheet(&self, year: u32, totals: &mut YearlyTotals) {
+        let values: Vec<(AccountType, f64)> = self
+            .account_order
+            .iter()
+            .map(|uuid| &self.data.accounts[uuid])
+            .filter_map(|account| {
+                account
+                    .balance_sheet_value(year)
+                    .map(|value| (account.type_id(), value))
+            })
+            .collect();
+        let equity: f64 = self
+            .account_order
+            .iter()
+            .map(|uuid| &self.data.accounts[uuid])
+            .filter(|account| match account.type_id() {
+                AccountType::RealEstate => true,
+                AccountType::Mortgage => account.link_id().is_some(),
+                _ => false,
+            })
+            .filter_map(|account| account.balance_sheet_value(year))
+            .sum();
+        totals.record_balance_sheet(year, &values, equity);
+    }
+
+    /// Split the cost of living across accounts that use planned withdrawals
+    ///
+    /// Only accounts that are in their withdrawal period take part.  The planned withdrawals
+    /// are made after every account has been simulated so the balances for the year are known.
+    fn drawdown(&mut self, year: u32, totals: &mut YearlyTotals) -> Result<(), SimulationError> {
+        let strategy = match self.data.settings.drawdown {
+            Some(strategy) => strategy,
+            None => return Ok(()),
+        };
+        let settings = &self.data.settings;
+
+        let mut accounts: Vec<DrawdownAccount> = Vec::new();
+        for uuid in self.account_order.iter() {
+            let account = &self.data.accounts[uuid];
+            if account.withdrawal_type() != Some(WithdrawalOptions::Planned) {
+                continue;
+            }
+            let in_range = account
+                .get_range_out(settings, self.linked_dates(uuid)?)
+                .is_some_and(|range| range.contains(year));
+            if let (true, Some(tax_status)) = (in_range, account.tax_status()) {
+                accounts.push(DrawdownAccount {
+                    uuid: uuid.to_string(),
+                    balance: account.get_value(year).unwrap_or_default(),
+                    tax_status,
+                });
+            }
+        }
+        if accounts.is_empty() {
+            return Ok(());
+        }
+
+        let col_scale = match settings.is_retired(year) {
+            true => settings.retirement_cost_of_living / 100_f64,
+            false => 1_f64,
+        };
+        let need = totals.get_col(year) * col_scale;
+        let taxable_income = totals.income_taxable.get(year).unwrap_or_default();
+        let tax_rate = settings.income_tax(taxable_income, year).marginal_rate;
+        let bracket_room = settings.low_bracket_limit(year) - taxable_income;
+
+        let withdrawals = plan_drawdown(strategy, need, &accounts, tax_rate, bracket_room);
+        for (account, withdrawal) in accounts.iter().zip(withdrawals) {
+            if withdrawal > 0_f64 {
+                let impact =
+                    self.apply_to_account(&account.uuid, year, |a| a.withdraw(year, withdrawal))?;
+                totals.update(year, impact);
+            }
+        }
+        Ok(())
+    }
+
+    /// Deposit or withdraw the money from one time events that have a destination account
+    ///
+    /// Money received is deposited in the destination account.  Money spent is withdrawn from
+    /// the destination account and whatever the account can not cover is paid from net.
+    fn transfer_events(
+        &mut self,
+        year: u32,
+        totals: &mut YearlyTotals,
+    ) -> Result<(), SimulationError> {
+        let transfers: Vec<(String, f64)> = self
+            .account_order
+            .iter()
+            .map(|uuid| &self.data.accounts[uuid])
+            .filter_map(|account| account.destination().zip(account.get_value(year)))
+            .filter(|(_, amount)| *amount != 0_f64)
+            .collect();
+        for (uuid, amount) in transfers {
+            if amount > 0_f64 {
+                let deposit = self.deposit_within_limit(&uuid, year, amount, totals)?;
+                // Whatever is over the contribution limit of the destination account stays in net
+                totals.update(
+                    year,
+                    YearlyImpact {
+                        income: amount - deposit.saving,
+                        ..deposit
+                    },
+                );
+            } else {
+                let withdrawal =
+                    self.apply_to_account(&uuid, year, |a| a.withdraw(year, -amount))?;
+                // The withdrawal is spent on the event so it never reaches net
+                totals.update(
+                    year,
+                    YearlyImpact {
+                        expense: -amount - withdrawal.income,
+                        income: 0_f64,
+                        ..withdrawal
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Make the Roth conversion for the year if one is planned
+    ///
+    /// The converted amount is taxed as income but is moved straight into the Roth account
+    /// so it is never available to spend.  Conversions are not early withdrawals so no penalty is owed.
+    fn convert(&mut self, year: u32, totals: &mut YearlyTotals) -> Result<(), SimulationError> {
+        let conversion = match &self.data.settings.roth_conversion {
+            Some(conversion) => conversion.clone(),
+            None => return Ok(()),
+        };
+        if !conversion.years(&self.data.settings).contains(year) {
+            return Ok(());
+        }
+
+        let taxable_income = totals.income_taxable.get(year).unwrap_or_default();
+        let amount = conversion
+            .amount(year, taxable_income, &self.data.settings)
+            .map_err(|cause| SimulationError::Simulate {
+                uuid: conversion.from_account.clone(),
+                name: self.data.accounts[&conversion.from_account].name(),
+                year,
+                cause,
+            })?;
+        if amount <= 0_f64 {
+            return Ok(());
+        }
+
+        let withdrawal = self.apply_to_account(&conversion.from_account, year, |a| {
+            a.withdraw(year, amount)
+        })?;
+        totals.update(
+            year,
+            YearlyImpact {
+                income: 0_f64,
+                penalty: 0_f64,
+                ..withdrawal
+            },
+        );
+        let converted = withdrawal.income;
+        let deposit =
+            self.apply_to_account(&conversion.to_account, year, |a| {
+                // Conversions do not count toward the contribution limit
+                a.deposit(year, converted, None)
+            })?;
+        totals.update(year, deposit);
+        Ok(())
+    }
+
+    /// Invest surplus cash and cover deficits at the end of a year based on the cash sweep settings
+    ///
+    /// Net above the cash buffer is split evenly between the deposit accounts.  A negative net
+    /// is covered by withdrawing from accounts in priority order.  Withdrawals can add taxable
+    /// income so taxes are paid again after each pass until net is no longer negative.
+    fn sweep_cash(&mut self, year: u32, totals: &mut YearlyTotals) -> Result<(), SimulationError> {
+        let sweep = match &self.data.settings.cash_sweep {
+            Some(sweep) => sweep.clone(),
+            None => return Ok(()),
+        };
+
+        // Deposit the surplus
+        let buffer = fixed_with_inflation(sweep.cash_buffer, year, &self.data.settings);
+        let surplus = totals.net.get(year).unwrap_or_default() - buffer;
+        if surplus > 0_f64 && !sweep.deposit_accounts.is_empty() {
+            let share = surplus / sweep.deposit_accounts.len() as f64;
+            for uuid in sweep.deposit_accounts.iter() {
+                // Whatever is over the contribution limit of the account stays in net
+                let impact = self.deposit_within_limit(uuid, year, share, totals)?;
+                totals.update(year, impact);
+                totals.net.update(year, -impact.saving);
+            }
+        }
+
+        // Cover the deficit
+        for _ in 0..MAX_SWEEP_PASSES {
+            let mut withdrawn = 0_f64;
+            for uuid in sweep.withdrawal_order.iter() {
+                let deficit = -totals.net.get(year).unwrap_or_default();
+                if deficit <= 0_f64 {
+                    break;
+                }
+                let impact =
+                    self.apply_to_account(uuid, year, |account| account.withdraw(year, deficit))?;
+                totals.update(year, impact);
+                totals.net.update(year, impact.income - impact.penalty);
+                withdrawn += impact.income;
+            }
+            if withdrawn <= 0_f64 {
+                break;
+            }
+            totals.pay_income_tax_from_net(year, &self.data.settings);
+            totals.pay_capital_gains_tax_from_net(year, &self.data.settings);
+        }
+        Ok(())
+    }
+
+    /// Deposit money into an account capped at the contribution room left for the year
+    ///
+    /// The amount over the limit is recorded as a contribution shortfall by the account and the
+    /// amount deposited counts toward the limit shared with the owner's other accounts.
+    fn deposit_within_limit(
+        &mut self,
+        uuid: &str,
+        year: u32,
+        amount: f64,
+        totals: &mut YearlyTotals,
+    ) -> Result<YearlyImpact, SimulationError> {
+        let (limit, before) = match self.data.accounts.get(uuid) {
+            Some(account) => (
+                account.contribution_room(year, totals, &self.data.settings),
+                account.limited_contribution(year),
+            ),
+            None => (None, 0_f64),
+        };
+        let impact = self.apply_to_account(uuid, year, |a| a.deposit(year, amount, limit))?;
+        self.count_contribution(uuid, year, before, totals);
+        Ok(impact)
+    }
+
+    /// Count what an account contributed since `before` toward the limit shared with the other
+    /// accounts of the same owner and kind
+    fn count_contribution(&self, uuid: &str, year: u32, before: f64, totals: &mut YearlyTotals) {
+        let account = &self.data.accounts[uuid];
+        if let Some(kind) = account.limit_kind() {
+            let amount = account.limited_contribution(year) - before;
+            totals.add_limited_contribution(account.owner(), kind, year, amount);
+        }
+    }
+
+    /// Apply a deposit or withdrawal to an account after it has been simulated for the year
+    fn apply_to_account<F>(
+        &mut self,
+        uuid: &str,
+        year: u32,
+        f: F,
+    ) -> Result<YearlyImpact, SimulationError>
+    where
+        F: FnOnce(&mut Box<dyn Account>) -> Result<YearlyImpact, Box<dyn Error>>,
+    {
+        let account =
+            self.data
+                .accounts
+                .get_mut(uuid)
+                .ok_or_else(|| SimulationError::MissingAccount {
+                    uuid: uuid.to_string(),
+                })?;
+        f(account).map_err(|e| SimulationError::Simulate {
+            uuid: uuid.to_string(),
+            name: account.name(),
+            year,
+            cause: e.to_string(),
+        })
+    }
+
+    /// Get dates from the linked account if the account has a link ID
+    ///
+    /// This explicitly does not allow recursion in linked_dates
+    fn linked_dates(&self, uuid: &str) -> Result<Option<Dates>, SimulationError> {
+        let account = &self.data.accounts[uuid];
+        match account.link_id() {
+            Some(link_id) => match self.data.accounts.get(&link_id) {
+                Some(linked) => Ok(Some(Dates {
+                    year_in: linked.get_range_in(&self.data.settings, None),
+                    year_out: linked.get_range_out(&self.data.settings, None),
+                })),
+                None => Err(SimulationError::MissingLink {
+                    uuid: uuid.to_string(),
+                    name: account.name(),
+                    link_id,
+                }),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Get the value of the linked account for a year if the account has a link ID
+    ///
+    /// Linked accounts are processed earlier in the account order so their value for
+    /// the year is already computed.
+    fn linked_value(&self, uuid: &str, year: u32) -> Option<f64> {
+        self.data.accounts[uuid]
+            .link_id()
+            .and_then(|link_id| self.data.accounts.get(&link_id))
+            .and_then(|linked| linked.linked_value(year, &self.data.settings))
+    }
+}
+
+impl SimulationResult {
+    /// Check if the plan worked (net never dropped below zero)
+    pub fn succeeded(&self) -> bool {
+        self.years
+            .iter()
+            .all(|year| self.totals.net.get(*year).unwrap_or_default() >= 0_f64)
+    }
+    /// Return analysis data for every account (keyed by uuid) to use in UI plotting
+    pub fn plot_data(&self) -> HashMap<String, Vec<PlotDataSet>> {
+        self.data
+            .accounts
+            .iter()
+            .map(|(uuid, account)| (uuid.to_string(), account.get_plot_data()))
+            .collect()
+    }
+    /// Return the amortization summary of every account that has one (keyed by uuid)
+    pub fn amortization_summaries(&self) -> HashMap<String, AmortizationSummary> {
+        self.data
+            .accounts
+            .iter()
+            .filter_map(|(uuid, account)| {
+                account
+                    .amortization_summary()
+                    .map(|summary| (uuid.to_string(), summary))
+            })
+            .collect()
+    }
+    /// Gather the results the UI shows for the simulation
+    pub fn analysis_results(self) -> AnalysisResults {
+        AnalysisResults {
+            plot_data: self.plot_data(),
+            amortization: self.amortization_summaries(),
+            penalty_years: self.totals.penalty_years(),
+            totals: self.totals,
+        }
+    }
+}
+
+/// Run the simulation for a set of user data
+pub fn run(data: UserData<Box<dyn Account>>) -> Result<SimulationResult, SimulationError> {
+    Simulation::new(data).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+    use crate::inputs::{
+        CashSweep, ContributionLimit, ContributionLimits, ConversionOptions, DrawdownStrategy,
+        EarlyWithdrawalPenalties, EarlyWithdrawalPenalty, RothConversion, Spouse, YearInput,
+    };
+
+    /// Generate a retirement account with employer matching that is linked to `income_link`
+    fn test_retirement(income_link: Option<&str>) -> AccountWrapper {
+        let json = format!(
+            r#"{{
+                "type": "retirement",
+                "name": "401k",
+                "table": {{}},
+                "startIn": 2000,
+                "endIn": 2010,
+                "startOut": 2011,
+                "endOut": 2020,
+                "contributionValue": 100,
+                "contributionType": "fixed",
+                "yearlyReturn": 5,
+                "withdrawalType": "other",
+                "withdrawalValue": 0,
+                "taxStatus": "contribute_pretax_taxed_when_used",
+                "incomeLink": {},
+                "matching": {{"amount": 50, "limit": 6}}
+            }}"#,
+            serde_json::json!(income_link)
+        );
+        serde_json::from_str::<AccountWrapper>(&json).unwrap()
+    }
+
+    /// Generate a retirement account holding 10,000 with no contributions or earnings
+    fn test_sweep_account(tax_status: &str, withdrawal_type: &str) -> AccountWrapper {
+        let json = format!(
+            r#"{{
+                "type": "retirement",
+                "name": "Brokerage",
+                "table": {{"1999": 10000}},
+                "startIn": 2000,
+                "endIn": 2010,
+                "startOut": 2000,
+                "endOut": 2020,
+                "contributionValue": 0,
+                "contributionType": "fixed",
+                "yearlyReturn": 0,
+                "withdrawalType": "{}",
+                "withdrawalValue": 0,
+                "taxStatus": "{}"
+            }}"#,
+            withdrawal_type, tax_status
+        );
+        serde_json::from_str::<AccountWrapper>(&json).unwrap()
+    }
+
+    /// Generate an income account of 1000 a year that belongs to your spouse
+    fn test_spouse_income() -> AccountWrapper {
+        let json = r#"{
+            "type": "income",
+            "name": "Spouse Job",
+            "table": {},
+            "base": 1000,
+            "startIn": 2000,
+            "endIn": 2010,
+            "raise": 0,
+            "owner": "spouse"
+        }"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Generate a small plan with one income and one expense account
+    fn test_user_data() -> UserData<Box<dyn Account>> {
+        let json = r#"{
+            "settings": {
+                "ageRetire": 50,
+                "ageDie": 25,
+                "yearBorn": 1980,
+                "yearStart": 2000,
+                "inflationBase": 5.0,
+                "taxIncome": 20.0,
+                "taxCapitalGains": 10.0,
+                "retirementCostOfLiving": 80.0,
+                "ssa": {
+                    "breakpoints": {"low": 30000, "high": 40000},
+                    "taxableIncomePercentage": {"low": 50, "high": 80}
+                }
+            },
+            "accounts": {
+                "income": {
+                    "type": "income",
+                    "name": "Job",
+                    "table": {},
+                    "base": 1000,
+                    "startIn": 2000,
+                    "endIn": 2010,
+                    "raise": 0
+                },
+                "expense": {
+                    "type": "expense",
+                    "name": "Food",
+                    "table": {},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "expenseType": "fixed",
+                    "expenseValue": 300,
+                    "isHealthcare": false
+                }
+            }
+        }"#;
+        serde_json::from_str::<UserData<AccountWrapper>>(json)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn simulation_order_and_years() {
+        let simulation = Simulation::new(test_user_data());
+        assert_eq!(simulation.account_order, vec!["income", "expense"]);
+        assert_eq!(simulation.years.first(), Some(&2000));
+        assert_eq!(simulation.years.last(), Some(&2004));
+    }
+
+    #[test]
+    fn simulation_run() {
+        let result = run(test_user_data()).unwrap();
+        assert_eq!(result.totals.years(), result.years);
+        // income of 1000 less 20% tax less 300 of expenses accumulates in net each year
+        assert_approx_eq!(f64, result.totals.net.get(2000).unwrap(), 500_f64);
+        assert_approx_eq!(f64, result.totals.net.get(2004).unwrap(), 2500_f64);
+        assert_eq!(result.plot_data().len(), 2);
+    }
+
+    #[test]
+    fn simulation_missing_link() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "retirement".into(),
+            test_retirement(Some("nope")).to_account_object(),
+        );
+        let error = run(data).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::MissingLink {
+                uuid: "retirement".into(),
+                name: "401k".into(),
+                link_id: "nope".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn simulation_account_error() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "retirement".into(),
+            test_retirement(None).to_account_object(),
+        );
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "retirement");
+        assert_eq!(error.name(), "401k");
+        assert_eq!(error.year(), Some(2000));
+    }
+
+    #[test]
+    fn simulation_household() {
+        let mut data = test_user_data();
+        data.accounts
+            .insert("spouse".into(), test_spouse_income().to_account_object());
+        data.settings.spouse = Some(Spouse {
+            age_retire: 50,
+            age_die: 25,
+            year_born: 1982,
+        });
+        let result = run(data).unwrap();
+        // the simulation runs until your spouse dies two years after you
+        assert_eq!(result.years.last(), Some(&2006));
+        assert_approx_eq!(f64, result.totals.get_income(2004), 2000_f64);
+        // only your spouse earns income after you die
+        assert_approx_eq!(f64, result.totals.get_income(2005), 1000_f64);
+    }
+
+    #[test]
+    fn simulation_missing_spouse() {
+        let mut data = test_user_data();
+        data.accounts
+            .insert("spouse".into(), test_spouse_income().to_account_object());
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "spouse");
+        assert_eq!(error.year(), None);
+    }
+
+    #[test]
+    fn simulation_sweep_surplus() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "brokerage".into(),
+            test_sweep_account("contribute_taxed_earnings_taxed_when_used", "other")
+                .to_account_object(),
+        );
+        data.settings.cash_sweep = Some(CashSweep {
+            cash_buffer: 100_f64,
+            deposit_accounts: vec!["brokerage".into()],
+            withdrawal_order: vec![],
+        });
+        let result = run(data).unwrap();
+        // net of 500 less the 100 buffer is deposited
+        assert_approx_eq!(f64, result.totals.net.get(2000).unwrap(), 100_f64);
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["brokerage"].get_value(2000).unwrap(),
+            10_400_f64
+        );
+    }
+
+    #[test]
+    fn simulation_sweep_deficit() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "expense".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "expense",
+                    "name": "Rent",
+                    "table": {},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "expenseType": "fixed",
+                    "expenseValue": 1500,
+                    "isHealthcare": false
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.settings.cash_sweep = Some(CashSweep {
+            cash_buffer: 0_f64,
+            deposit_accounts: vec![],
+            withdrawal_order: vec!["ira".into()],
+        });
+        let result = run(data).unwrap();
+        // deficit of 700 is covered by withdrawing enough to also pay the 20% tax on the withdrawal
+        assert_approx_eq!(
+            f64,
+            result.totals.net.get(2000).unwrap(),
+            0_f64,
+            epsilon = 0.01
+        );
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["ira"].get_value(2000).unwrap(),
+            10_000_f64 - 875_f64,
+            epsilon = 0.01
+        );
+    }
+
+    #[test]
+    fn simulation_early_withdrawal_penalty() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "expense".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "expense",
+                    "name": "Rent",
+                    "table": {},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "expenseType": "fixed",
+                    "expenseValue": 1500,
+                    "isHealthcare": false
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.settings.cash_sweep = Some(CashSweep {
+            cash_buffer: 0_f64,
+            deposit_accounts: vec![],
+            withdrawal_order: vec!["ira".into()],
+        });
+        let penalty = EarlyWithdrawalPenalty {
+            age: 59.5,
+            rate: 10_f64,
+        };
+        data.settings.early_withdrawal_penalties = Some(EarlyWithdrawalPenalties {
+            retirement: penalty.clone(),
+            hsa: penalty,
+        });
+        let result = run(data).unwrap();
+        // deficit of 700 is covered by withdrawing enough to also pay the 20% tax and 10% penalty
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["ira"].get_value(2000).unwrap(),
+            9_000_f64,
+            epsilon = 0.01
+        );
+        assert_approx_eq!(
+            f64,
+            result.totals.penalty.get(2000).unwrap(),
+            100_f64,
+            epsilon = 0.01
+        );
+        assert_eq!(result.totals.penalty_years(), vec![2000, 2001, 2002, 2003, 2004]);
+        let results = result.analysis_results();
+        assert_eq!(results.penalty_years, vec![2000, 2001, 2002, 2003, 2004]);
+    }
+
+    #[test]
+    fn simulation_mortgage_amortization() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "mortgage".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "mortgage",
+                    "name": "House",
+                    "table": {"1999": 12000},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "paymentType": "fixed",
+                    "paymentValue": 0,
+                    "rate": 0,
+                    "compoundTime": 12,
+                    "mortgageInsurance": 0,
+                    "ltvLimit": 80,
+                    "escrowValue": 100,
+                    "homeValue": 100000,
+                    "amortization": {"term": 24, "extraPrincipal": 500}
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let result = run(data).unwrap();
+        // 500 a month plus 500 of extra principal pays off the loan in a year
+        let mortgage = &result.data.accounts["mortgage"];
+        assert_approx_eq!(f64, mortgage.get_value(2000).unwrap(), 0_f64);
+        // food expense plus the loan and escrow
+        assert_approx_eq!(f64, result.totals.expense.get(2000).unwrap(), 12_400_f64);
+        assert_approx_eq!(f64, result.totals.expense.get(2001).unwrap(), 300_f64);
+        // the payoff date is sent to the UI with the results
+        let summary = result.analysis_results().amortization["mortgage"];
+        assert_eq!((summary.payoff_year, summary.payoff_month), (2000, 12));
+        assert_approx_eq!(f64, summary.total_interest, 0_f64);
+    }
+
+    #[test]
+    fn simulation_loan_refinance() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "loan".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "loan",
+                    "name": "Car",
+                    "table": {"1999": 10000},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "paymentType": "amortized",
+                    "paymentValue": 0,
+                    "term": 10,
+                    "rate": 10,
+                    "changes": [
+                        {"year": 2002, "rate": 0, "term": 2, "caps": null, "closingCosts": 500}
+                    ]
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let result = run(data).unwrap();
+        let loan = &result.data.accounts["loan"];
+        // the refinance pays off what is left in two years without interest
+        let balance = loan.get_value(2001).unwrap();
+        assert_approx_eq!(f64, loan.get_value(2002).unwrap(), balance / 2_f64);
+        assert_approx_eq!(f64, loan.get_value(2003).unwrap(), 0_f64);
+        // food expense plus the payment and closing costs
+        assert_approx_eq!(
+            f64,
+            result.totals.expense.get(2002).unwrap(),
+            300_f64 + balance / 2_f64 + 500_f64
+        );
+    }
+
+    #[test]
+    fn simulation_amortized_loan_without_term() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "loan".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "loan",
+                    "name": "Car",
+                    "table": {"1999": 10000},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "paymentType": "amortized",
+                    "paymentValue": 10,
+                    "rate": 10,
+                    "changes": null
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "loan");
+    }
+
+    #[test]
+    fn simulation_mortgage_escrow() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "mortgage".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "mortgage",
+                    "name": "House",
+                    "table": {"1999": 1000},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "paymentType": "amortized",
+                    "paymentValue": 0,
+                    "term": 1,
+                    "rate": 0,
+                    "compoundTime": 1,
+                    "mortgageInsurance": 0,
+                    "ltvLimit": 80,
+                    "escrowValue": 0,
+                    "escrow": {
+                        "assessedValue": 100000,
+                        "assessmentGrowth": 10,
+                        "propertyTaxRate": 2,
+                        "insurance": 500,
+                        "insuranceInflation": 20
+                    },
+                    "homeValue": 100000
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let result = run(data).unwrap();
+        assert_approx_eq!(f64, result.data.accounts["mortgage"].get_value(2000).unwrap(), 0_f64);
+        // food expense plus the loan, property tax, and insurance
+        assert_approx_eq!(f64, result.totals.expense.get(2000).unwrap(), 3_800_f64);
+        // property tax and insurance continue to grow after the loan is paid off
+        assert_approx_eq!(
+            f64,
+            result.totals.expense.get(2001).unwrap(),
+            300_f64 + 2_200_f64 + 600_f64,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn simulation_real_estate_equity() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "house".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "realEstate",
+                    "name": "House",
+                    "table": {},
+                    "purchasePrice": 100000,
+                    "appreciation": 10
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        data.accounts.insert(
+            "mortgage".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "mortgage",
+                    "name": "Mortgage",
+                    "table": {"1999": 90000},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "paymentType": "fixed",
+                    "paymentValue": 5000,
+                    "rate": 0,
+                    "compoundTime": 1,
+                    "mortgageInsurance": 1000,
+                    "ltvLimit": 80,
+                    "escrowValue": 0,
+                    "homeValue": 100000,
+                    "propertyLink": "house"
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let result = run(data).unwrap();
+        let house = &result.data.accounts["house"];
+        let mortgage = &result.data.accounts["mortgage"];
+        assert_approx_eq!(f64, house.get_value(2000).unwrap(), 100_000_f64);
+        assert_approx_eq!(f64, house.get_value(2001).unwrap(), 110_000_f64, epsilon = 1e-6);
+        // insurance is paid until the appreciated house brings loan to value under 80%
+        assert_approx_eq!(f64, mortgage.get_value(2000).unwrap(), 86_000_f64);
+        assert_approx_eq!(f64, mortgage.get_value(2001).unwrap(), 81_000_f64);
+        assert_approx_eq!(
+            f64,
+            result.totals.equity.get(2001).unwrap(),
+            29_000_f64,
+            epsilon = 1e-6
+        );
+        assert_approx_eq!(
+            f64,
+            result.totals.net_worth.get(2001).unwrap(),
+            result.totals.net.get(2001).unwrap() + 29_000_f64,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn simulation_balance_sheet() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "brokerage".into(),
+            test_sweep_account("contribute_taxed_earnings_taxed_when_used", "other")
+                .to_account_object(),
+        );
+        data.accounts.insert(
+            "loan".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "loan",
+                    "name": "Car",
+                    "table": {"1999": 4000},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "paymentType": "fixed",
+                    "paymentValue": 1000,
+                    "rate": 0
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let result = run(data).unwrap();
+        let totals = &result.totals;
+        let saving = result.data.accounts["brokerage"].get_value(2001).unwrap();
+        assert_approx_eq!(f64, totals.assets.get(2001).unwrap(), saving);
+        assert_approx_eq!(f64, totals.liabilities.get(2001).unwrap(), 2_000_f64);
+        assert_approx_eq!(
+            f64,
+            totals.balance_sheet[&AccountType::Loan].get(2001).unwrap(),
+            -2_000_f64
+        );
+        // the balance sheet is keyed by the same account type names the UI and csv summary use
+        assert_eq!(
+            serde_json::to_value(AccountType::RealEstate).unwrap(),
+            AccountType::RealEstate.to_string()
+        );
+        assert_eq!(AccountType::RealEstate.to_string(), "realEstate");
+        assert_approx_eq!(
+            f64,
+            totals.net_worth.get(2001).unwrap(),
+            totals.net.get(2001).unwrap() + saving - 2_000_f64
+        );
+        // loans that are not linked to real estate are not part of home equity
+        assert_approx_eq!(f64, totals.equity.get(2001).unwrap(), 0_f64);
+    }
+
+    #[test]
+    fn simulation_event_destination() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "brokerage".into(),
+            test_sweep_account("contribute_taxed_earnings_taxed_when_used", "other")
+                .to_account_object(),
+        );
+        for (uuid, year, amount) in [("sale", 2001, 5_000_f64), ("purchase", 2002, -16_000_f64)] {
+            let json = format!(
+                r#"{{
+                    "type": "event",
+                    "name": "Event",
+                    "table": {{}},
+                    "year": {},
+                    "amount": {},
+                    "taxTreatment": "capital_gains",
+                    "costBasis": 3000,
+                    "destination": "brokerage"
+                }}"#,
+                year, amount
+            );
+            data.accounts.insert(
+                uuid.into(),
+                serde_json::from_str::<AccountWrapper>(&json)
+                    .unwrap()
+                    .to_account_object(),
+            );
+        }
+        let result = run(data).unwrap();
+        let totals = &result.totals;
+        let brokerage = &result.data.accounts["brokerage"];
+        // sale proceeds land in the brokerage account and only the gain is taxed
+        assert_approx_eq!(f64, brokerage.get_value(2001).unwrap(), 15_000_f64);
+        assert_approx_eq!(f64, totals.capital_gains.get(2001).unwrap(), 2_000_f64);
+        assert_approx_eq!(f64, totals.income.get(2001).unwrap(), 1_000_f64);
+        // the purchase empties the brokerage account and the rest is paid from net
+        assert_approx_eq!(f64, brokerage.get_value(2002).unwrap(), 0_f64);
+        assert_approx_eq!(f64, totals.expense.get(2002).unwrap(), 1_300_f64);
+        assert_approx_eq!(f64, totals.income.get(2002).unwrap(), 1_000_f64);
+    }
+
+    #[test]
+    fn simulation_event_missing_destination() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "event".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "event",
+                    "name": "Inheritance",
+                    "table": {},
+                    "year": 2001,
+                    "amount": 1000,
+                    "taxTreatment": "untaxed",
+                    "destination": "nope"
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "event");
+    }
+
+    #[test]
+    fn simulation_event_pretax_destination() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.accounts.insert(
+            "event".into(),
+            serde_json::from_str::<AccountWrapper>(
+                r#"{
+                    "type": "event",
+                    "name": "Inheritance",
+                    "table": {},
+                    "year": 2001,
+                    "amount": 1000,
+                    "taxTreatment": "untaxed",
+                    "destination": "ira"
+                }"#,
+            )
+            .unwrap()
+            .to_account_object(),
+        );
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "event");
+    }
+
+    #[test]
+    fn simulation_sweep_pretax_deposit() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.settings.cash_sweep = Some(CashSweep {
+            cash_buffer: 0_f64,
+            deposit_accounts: vec!["ira".into()],
+            withdrawal_order: vec!["ira".into()],
+        });
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "ira");
+    }
+
+    #[test]
+    fn simulation_sweep_missing_account() {
+        let mut data = test_user_data();
+        data.settings.cash_sweep = Some(CashSweep {
+            cash_buffer: 0_f64,
+            deposit_accounts: vec!["nope".into()],
+            withdrawal_order: vec![],
+        });
+        let error = run(data).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::MissingAccount {
+                uuid: "nope".into()
+            }
+        );
+    }
+
+    #[test]
+    fn simulation_drawdown() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "roth".into(),
+            test_sweep_account("contribute_taxed_earnings_untaxed_when_used", "planned")
+                .to_account_object(),
+        );
+        data.accounts.insert(
+            "brokerage".into(),
+            test_sweep_account("contribute_taxed_earnings_taxed_when_used", "planned")
+                .to_account_object(),
+        );
+        data.settings.drawdown = Some(DrawdownStrategy::TaxableFirst);
+        let result = run(data).unwrap();
+        // the 300 cost of living comes out of the brokerage account only
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["brokerage"].get_value(2000).unwrap(),
+            9_700_f64
+        );
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["roth"].get_value(2000).unwrap(),
+            10_000_f64
+        );
+    }
+
+    #[test]
+    fn simulation_employer_match() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "retirement".into(),
+            test_retirement(Some("income")).to_account_object(),
+        );
+        let result = run(data).unwrap();
+        // contribution of 100 plus 50% match on the first 6% of the 1000 linked income
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["retirement"].get_value(2000).unwrap(),
+            130_f64
+        );
+        assert_approx_eq!(f64, result.totals.get_saving(2000), 130_f64);
+    }
+
+    #[test]
+    fn simulation_roth_conversion() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.accounts.insert(
+            "roth".into(),
+            test_sweep_account("contribute_taxed_earnings_untaxed_when_used", "other")
+                .to_account_object(),
+        );
+        data.settings.roth_conversion = Some(RothConversion {
+            from_account: "ira".into(),
+            to_account: "roth".into(),
+            start_year: YearInput::ConstantInt(2000),
+            end_year: YearInput::ConstantInt(2001),
+            conversion_type: ConversionOptions::Fixed,
+            conversion_value: 2_000_f64,
+        });
+        let result = run(data).unwrap();
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["ira"].get_value(2001).unwrap(),
+            6_000_f64
+        );
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["roth"].get_value(2001).unwrap(),
+            14_000_f64
+        );
+        // the conversion is taxed but none of it is available to spend
+        assert_approx_eq!(f64, result.totals.income_taxable.get(2000).unwrap(), 3_000_f64);
+        assert_approx_eq!(f64, result.totals.tax_burden.get(2000).unwrap(), 600_f64);
+        assert_approx_eq!(f64, result.totals.net.get(2000).unwrap(), 100_f64);
+        // no conversion after the end year
+        assert_approx_eq!(f64, result.totals.income_taxable.get(2002).unwrap(), 1_000_f64);
+    }
+
+    #[test]
+    fn simulation_roth_conversion_invalid_account() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "roth".into(),
+            test_sweep_account("contribute_taxed_earnings_untaxed_when_used", "other")
+                .to_account_object(),
+        );
+        data.settings.roth_conversion = Some(RothConversion {
+            from_account: "income".into(),
+            to_account: "roth".into(),
+            start_year: YearInput::ConstantInt(2000),
+            end_year: YearInput::ConstantInt(2001),
+            conversion_type: ConversionOptions::Fixed,
+            conversion_value: 2_000_f64,
+        });
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "income");
+    }
+
+    #[test]
+    fn simulation_roth_conversion_to_pretax() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.accounts.insert(
+            "401k".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.settings.roth_conversion = Some(RothConversion {
+            from_account: "ira".into(),
+            to_account: "401k".into(),
+            start_year: YearInput::ConstantInt(2000),
+            end_year: YearInput::ConstantInt(2001),
+            conversion_type: ConversionOptions::Fixed,
+            conversion_value: 2_000_f64,
+        });
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "401k");
+    }
+
+    #[test]
+    fn simulation_roth_conversion_same_account() {
+        let mut data = test_user_data();
+        data.accounts.insert(
+            "ira".into(),
+            test_sweep_account("contribute_pretax_taxed_when_used", "other").to_account_object(),
+        );
+        data.settings.roth_conversion = Some(RothConversion {
+            from_account: "ira".into(),
+            to_account: "ira".into(),
+            start_year: YearInput::ConstantInt(2000),
+            end_year: YearInput::ConstantInt(2001),
+            conversion_type: ConversionOptions::Fixed,
+            conversion_value: 2_000_f64,
+        });
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "ira");
+        assert!(error.to_string().contains("different accounts"));
+    }
+
+    /// Generate an IRA holding 10,000 that contributes `contribution` a year
+    fn test_ira(tax_status: &str, contribution: f64) -> Box<dyn Account> {
+        let mut json = serde_json::to_value(test_sweep_account(tax_status, "other")).unwrap();
+        json["contributionValue"] = contribution.into();
+        json["limitKind"] = "ira".into();
+        serde_json::from_value::<AccountWrapper>(json)
+            .unwrap()
+            .to_account_object()
+    }
+
+    /// Set the IRA limit to 300 a year (workplace and HSA limits are not reached by the tests)
+    fn test_contribution_limits(data: &mut UserData<Box<dyn Account>>) {
+        let limit = |limit: f64| ContributionLimit {
+            limit,
+            catch_up: 0_f64,
+            catch_up_age: 50,
+        };
+        data.settings.contribution_limits = Some(ContributionLimits {
+            workplace: limit(20_000_f64),
+            ira: limit(300_f64),
+            hsa: limit(3_000_f64),
+        });
+    }
+
+    #[test]
+    fn simulation_shared_contribution_limit() {
+        let mut data = test_user_data();
+        test_contribution_limits(&mut data);
+        data.accounts.insert("ira".into(), test_ira("contribute_pretax_taxed_when_used", 200_f64));
+        data.accounts.insert(
+            "roth".into(),
+            test_ira("contribute_taxed_earnings_untaxed_when_used", 200_f64),
+        );
+        let result = run(data).unwrap();
+        // both accounts share one limit so together they only contribute 300
+        let contributed: f64 = ["ira", "roth"]
+            .iter()
+            .map(|uuid| result.data.accounts[*uuid].get_value(2000).unwrap() - 10_000_f64)
+            .sum();
+        assert_approx_eq!(f64, contributed, 300_f64);
+    }
+
+    #[test]
+    fn simulation_sweep_contribution_limit() {
+        let mut data = test_user_data();
+        test_contribution_limits(&mut data);
+        data.accounts.insert(
+            "roth".into(),
+            test_ira("contribute_taxed_earnings_untaxed_when_used", 100_f64),
+        );
+        data.settings.cash_sweep = Some(CashSweep {
+            cash_buffer: 0_f64,
+            deposit_accounts: vec!["roth".into()],
+            withdrawal_order: vec![],
+        });
+        let result = run(data).unwrap();
+        // only 200 of the 400 surplus fits under the limit and the rest stays in net
+        assert_approx_eq!(
+            f64,
+            result.data.accounts["roth"].get_value(2000).unwrap(),
+            10_300_f64
+        );
+        assert_approx_eq!(f64, result.totals.net.get(2000).unwrap(), 200_f64);
+    }
+
+    #[test]
+    fn simulation_max_allowed_without_limit() {
+        let mut data = test_user_data();
+        let mut json = serde_json::to_value(test_retirement(None)).unwrap();
+        json["contributionType"] = "max_allowed".into();
+        json["matching"] = serde_json::Value::Null;
+        data.accounts.insert(
+            "retirement".into(),
+            serde_json::from_value::<AccountWrapper>(json)
+                .unwrap()
+                .to_account_object(),
+        );
+        let error = run(data).unwrap_err();
+        assert_eq!(error.uuid(), "retirement");
+    }
+}
@@ -0,0 +1,120 @@
+//! Errors that stop a simulation from completing
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+/// Error raised while running a [Simulation](super::Simulation)
+///
+/// Each variant other than [InvalidOptions](SimulationError::InvalidOptions) identifies the account
+/// that caused the error so the UI can point the user at it.
+#[derive(TS, Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum SimulationError {
+    /// The account could not be initialized
+    Init {
+        /// Id of the account
+        uuid: String,
+        /// Name of the account
+        name: String,
+        /// Description of what went wrong
+        cause: String,
+    },
+    /// The account could not be simulated for a year
+    Simulate {
+        /// Id of the account
+        uuid: String,
+        /// Name of the account
+        name: String,
+        /// Year being simulated
+        year: u32,
+        /// Description of what went wrong
+        cause: String,
+    },
+    /// The account is linked to an account that does not exist
+    MissingLink {
+        /// Id of the account
+        uuid: String,
+        /// Name of the account
+        name: String,
+        /// Id of the linked account that could not be found
+        #[serde(rename = "linkId")]
+        link_id: String,
+    },
+    /// The settings refer to an account that does not exist
+    MissingAccount {
+        /// Id of the account that could not be found
+        uuid: String,
+    },
+    /// The options for running the plan many times (such as Monte Carlo) are not valid
+    InvalidOptions {
+        /// Description of what went wrong
+        cause: String,
+    },
+}
+
+impl SimulationError {
+    /// Id of the account that caused the error (empty when no account caused it)
+    pub fn uuid(&self) -> &str {
+        match self {
+            Self::Init { uuid, .. } => uuid,
+            Self::Simulate { uuid, .. } => uuid,
+            Self::MissingLink { uuid, .. } => uuid,
+            Self::MissingAccount { uuid } => uuid,
+            Self::InvalidOptions { .. } => "",
+        }
+    }
+    /// Name of the account that caused the error (empty when the account does not exist)
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Init { name, .. } => name,
+            Self::Simulate { name, .. } => name,
+            Self::MissingLink { name, .. } => name,
+            Self::MissingAccount { .. } => "",
+            Self::InvalidOptions { .. } => "",
+        }
+    }
+    /// Year the error occured in (if it happened while simulating a year)
+    pub fn year(&self) -> Option<u32> {
+        match self {
+            Self::Simulate { year, .. } => Some(*year),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Init { uuid, name, cause } => {
+                write!(f, "Unable to initialize account {} ({}): {}", name, uuid, cause)
+            }
+            Self::Simulate {
+                uuid,
+                name,
+                year,
+                cause,
+            } => write!(
+                f,
+                "Unable to simulate account {} ({}) in {}: {}",
+                name, uuid, year, cause
+            ),
+            Self::MissingLink {
+                uuid,
+                name,
+                link_id,
+            } => write!(
+                f,
+                "Account {} ({}) is linked to account {} which does not exist",
+                name, uuid, link_id
+            ),
+            Self::MissingAccount { uuid } => {
+                write!(f, "Settings refer to account {} which does not exist", uuid)
+            }
+            Self::InvalidOptions { cause } => write!(f, "Invalid options: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
@@ -2,10 +2,24 @@
 
 use serde::{Deserialize, Serialize};
 
+mod amortization;
+mod backtest;
+mod conversion;
+mod drawdown;
+mod driver;
+mod error;
+mod monte_carlo;
 mod table;
 mod table_groups;
 mod totals;
 
+pub use amortization::*;
+pub use backtest::*;
+pub use conversion::*;
+pub use drawdown::*;
+pub use driver::*;
+pub use error::*;
+pub use monte_carlo::*;
 pub use table::*;
 pub use table_groups::*;
 pub use totals::*;
@@ -35,6 +49,25 @@ pub struct Dates {
     pub year_out: Option<YearRange>,
 }
 
+/// Early withdrawal penalty resolved to calendar years
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Penalty {
+    /// Last year withdrawals are penalized in
+    pub last_year: u32,
+    /// Penalty as a percent of the amount withdrawn
+    pub rate: f64,
+}
+
+impl Penalty {
+    /// Penalty owed on an amount withdrawn in the specified year
+    pub fn value(self, year: u32, amount: f64) -> f64 {
+        match year <= self.last_year {
+            true => amount * self.rate / 100_f64,
+            false => 0_f64,
+        }
+    }
+}
+
 /// Data point used in UI plotting
 #[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
 pub struct PlotDataPoint {
@@ -0,0 +1,354 @@
+//! Run a plan many times with random returns to estimate how likely it is to succeed
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use super::{run, SimulationError, Table};
+use crate::inputs::{MonteCarloOptions, ReturnDistribution, ReturnModel};
+use crate::{Account, AccountWrapper, UserData};
+
+/// 10th, 50th, and 90th percentile of a value in each year across all runs
+#[derive(TS, Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+pub struct PercentileBands {
+    /// 10th percentile for each year
+    pub p10: Vec<f64>,
+    /// Median for each year
+    pub p50: Vec<f64>,
+    /// 90th percentile for each year
+    pub p90: Vec<f64>,
+}
+
+impl PercentileBands {
+    /// Compute the bands from the values of each run (every run has one value per year)
+    pub fn new(runs: &[Vec<f64>]) -> PercentileBands {
+        let mut bands = PercentileBands::default();
+        let years = runs.first().map_or(0, |run| run.len());
+        for idx in 0..years {
+            let mut values: Vec<f64> = runs.iter().map(|run| run[idx]).collect();
+            values.sort_by(|a, b| a.total_cmp(b));
+            bands.p10.push(percentile(&values, 10_f64));
+            bands.p50.push(percentile(&values, 50_f64));
+            bands.p90.push(percentile(&values, 90_f64));
+        }
+        bands
+    }
+}
+
+/// Results of running a plan many times with random returns
+#[derive(TS, Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloResult {
+    /// Number of runs made
+    pub runs: u32,
+    /// Number of runs where net never dropped below zero
+    pub successes: u32,
+    /// Number of runs that stopped with an error (these are counted as failed)
+    pub errors: u32,
+    /// Percent of runs that succeeded
+    pub success_probability: f64,
+    /// Years the simulation was run for
+    pub years: Vec<u32>,
+    /// Total savings in each year (only runs that completed have yearly values)
+    pub saving: PercentileBands,
+    /// Net cash in each year (only runs that completed have yearly values)
+    pub net: PercentileBands,
+}
+
+/// Value below which `percent` of the sorted values fall (nearest rank)
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0_f64;
+    }
+    let rank = (percent / 100_f64 * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank]
+}
+
+/// Build the normal distribution for a set of return inputs
+fn normal(distribution: &ReturnDistribution) -> Result<Normal<f64>, String> {
+    Normal::new(distribution.mean, distribution.volatility)
+        .map_err(|e| format!("Invalid return distribution: {}", e))
+}
+
+/// Draw a random return for every year
+fn draw(rng: &mut StdRng, distribution: &Normal<f64>, years: &[u32]) -> Table<u32> {
+    let returns: Vec<f64> = years.iter().map(|_| distribution.sample(rng)).collect();
+    Table::from((years.to_vec(), returns))
+}
+
+/// Run the plan `options.runs` times with random returns
+///
+/// Accounts listed in the options get a new random return every year.  Accounts in the same
+/// asset class share the same return each year.  A run succeeds if net never drops below zero.
+/// A run that stops with an error while simulating a year has failed.  Errors setting up the
+/// plan are the same in every run so they are returned instead.
+pub fn run_monte_carlo(
+    data: &UserData<AccountWrapper>,
+    options: &MonteCarloOptions,
+) -> Result<MonteCarloResult, SimulationError> {
+    // Sort the keys so the same seed always draws the same returns for the same accounts
+    let mut class_names: Vec<&String> = options.asset_classes.keys().collect();
+    class_names.sort();
+    let mut uuids: Vec<&String> = options.accounts.keys().collect();
+    uuids.sort();
+
+    let mut classes: HashMap<&String, Normal<f64>> = HashMap::new();
+    for name in class_names.iter() {
+        let distribution = normal(&options.asset_classes[*name]).map_err(|cause| {
+            SimulationError::InvalidOptions {
+                cause: format!("Asset class {}: {}", name, cause),
+            }
+        })?;
+        classes.insert(name, distribution);
+    }
+
+    // Check every account exists and has a valid return model before running anything
+    let mut accounts: HashMap<&String, Option<Normal<f64>>> = HashMap::new();
+    for uuid in uuids.iter() {
+        let account = data
+            .accounts
+            .get(*uuid)
+            .ok_or_else(|| SimulationError::MissingAccount {
+                uuid: uuid.to_string(),
+            })?;
+        let init_error = |cause: String| SimulationError::Init {
+            uuid: uuid.to_string(),
+            name: account.clone().to_account_object().name(),
+            cause,
+        };
+        let distribution = match &options.accounts[*uuid] {
+            ReturnModel::AssetClass(name) => match classes.contains_key(name) {
+                true => None,
+                false => return Err(init_error(format!("Unknown asset class {}", name))),
+            },
+            ReturnModel::Distribution(distribution) => {
+                Some(normal(distribution).map_err(init_error)?)
+            }
+        };
+        accounts.insert(uuid, distribution);
+    }
+
+    let years: Vec<u32> = (data.settings.year_start()..data.settings.year_end()).collect();
+    let mut rng = StdRng::seed_from_u64(u64::from(options.seed));
+    let mut saving_runs: Vec<Vec<f64>> = Vec::new();
+    let mut net_runs: Vec<Vec<f64>> = Vec::new();
+    let mut successes = 0;
+    let mut errors = 0;
+
+    for _ in 0..options.runs {
+        let class_returns: HashMap<&String, Table<u32>> = class_names
+            .iter()
+            .map(|name| (*name, draw(&mut rng, &classes[*name], &years)))
+            .collect();
+
+        let mut run_data: UserData<Box<dyn Account>> = data.clone().into();
+        for uuid in uuids.iter() {
+            let returns = match (&accounts[*uuid], &options.accounts[*uuid]) {
+                (Some(distribution), _) => draw(&mut rng, distribution, &years),
+                (None, ReturnModel::AssetClass(name)) => class_returns[name].clone(),
+                (None, ReturnModel::Distribution(_)) => unreachable!(),
+            };
+            run_data
+                .accounts
+                .get_mut(*uuid)
+                .unwrap()
+                .set_return_sequence(returns);
+        }
+
+        match run(run_data) {
+            Ok(result) => {
+                let net: Vec<f64> = years
+                    .iter()
+                    .map(|year| result.totals.net.get(*year).unwrap_or_default())
+                    .collect();
+                let saving: Vec<f64> = years
+                    .iter()
+                    .map(|year| result.totals.get_saving(*year))
+                    .collect();
+                if result.succeeded() {
+                    successes += 1;
+                }
+                net_runs.push(net);
+                saving_runs.push(saving);
+            }
+            // Returns can make a plan run out of money in ways that stop the simulation
+            Err(SimulationError::Simulate { .. }) => errors += 1,
+            Err(e) => return Err(e),
+        }
+    }
+
+    let runs = options.runs;
+    Ok(MonteCarloResult {
+        runs,
+        successes,
+        errors,
+        success_probability: match runs > 0 {
+            true => successes as f64 / runs as f64 * 100_f64,
+            false => 0_f64,
+        },
+        years,
+        saving: PercentileBands::new(&saving_runs),
+        net: PercentileBands::new(&net_runs),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use super::*;
+
+    /// Plan with a job that ends in 2002 and a savings account that pays for expenses
+    fn test_user_data() -> UserData<AccountWrapper> {
+        let json = r#"{
+            "settings": {
+                "ageRetire": 50,
+                "ageDie": 25,
+                "yearBorn": 1980,
+                "yearStart": 2000,
+                "inflationBase": 0.0,
+                "taxIncome": 0.0,
+                "taxCapitalGains": 0.0,
+                "retirementCostOfLiving": 100.0,
+                "ssa": {
+                    "breakpoints": {"low": 30000, "high": 40000},
+                    "taxableIncomePercentage": {"low": 50, "high": 80}
+                },
+                "cashSweep": {
+                    "cashBuffer": 0,
+                    "depositAccounts": ["savings"],
+                    "withdrawalOrder": ["savings"]
+                }
+            },
+            "accounts": {
+                "income": {
+                    "type": "income",
+                    "name": "Job",
+                    "table": {},
+                    "base": 1000,
+                    "startIn": 2000,
+                    "endIn": 2001,
+                    "raise": 0
+                },
+                "expense": {
+                    "type": "expense",
+                    "name": "Food",
+                    "table": {},
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "expenseType": "fixed",
+                    "expenseValue": 800,
+                    "isHealthcare": false
+                },
+                "savings": {
+                    "type": "savings",
+                    "name": "Savings",
+                    "table": {},
+                    "startIn": 2000,
+                    "endIn": 2010,
+                    "startOut": 2000,
+                    "endOut": 2010,
+                    "contributionValue": 0,
+                    "contributionType": "fixed",
+                    "yearlyReturn": 0,
+                    "withdrawalType": "other",
+                    "withdrawalValue": 0,
+                    "taxStatus": "contribute_taxed_earnings_untaxed_when_used"
+                }
+            }
+        }"#;
+        serde_json::from_str::<UserData<AccountWrapper>>(json).unwrap()
+    }
+
+    fn test_options(mean: f64, volatility: f64) -> MonteCarloOptions {
+        MonteCarloOptions {
+            runs: 50,
+            seed: 42,
+            asset_classes: HashMap::from([(
+                "stocks".to_string(),
+                ReturnDistribution { mean, volatility },
+            )]),
+            accounts: HashMap::from([(
+                "savings".to_string(),
+                ReturnModel::AssetClass("stocks".into()),
+            )]),
+        }
+    }
+
+    #[test]
+    fn percentiles() {
+        let runs: Vec<Vec<f64>> = (0..11).map(|value| vec![value as f64]).collect();
+        let bands = PercentileBands::new(&runs);
+        assert_approx_eq!(f64, bands.p10[0], 1_f64);
+        assert_approx_eq!(f64, bands.p50[0], 5_f64);
+        assert_approx_eq!(f64, bands.p90[0], 9_f64);
+    }
+
+    #[test]
+    fn monte_carlo_deterministic() {
+        // with no volatility every run is the same
+        let result = run_monte_carlo(&test_user_data(), &test_options(10_f64, 0_f64)).unwrap();
+        assert_eq!(result.runs, 50);
+        assert_eq!(result.years.len(), 5);
+        assert_approx_eq!(f64, result.saving.p10[0], result.saving.p90[0]);
+    }
+
+    #[test]
+    fn monte_carlo_seeded() {
+        let first = run_monte_carlo(&test_user_data(), &test_options(5_f64, 20_f64)).unwrap();
+        let second = run_monte_carlo(&test_user_data(), &test_options(5_f64, 20_f64)).unwrap();
+        assert_eq!(first, second);
+        assert!(first.saving.p10[4] <= first.saving.p50[4]);
+        assert!(first.saving.p50[4] <= first.saving.p90[4]);
+    }
+
+    #[test]
+    fn monte_carlo_success_probability() {
+        // savings of 400 cover the 800 of expenses after the job ends only with strong returns
+        let result = run_monte_carlo(&test_user_data(), &test_options(0_f64, 0_f64)).unwrap();
+        assert_approx_eq!(f64, result.success_probability, 0_f64);
+        let result = run_monte_carlo(&test_user_data(), &test_options(500_f64, 0_f64)).unwrap();
+        assert_approx_eq!(f64, result.success_probability, 100_f64);
+    }
+
+    #[test]
+    fn monte_carlo_errored_runs_fail() {
+        // covering the deficit from the job errors in every run that runs out of savings
+        let mut data = test_user_data();
+        data.settings
+            .cash_sweep
+            .as_mut()
+            .unwrap()
+            .withdrawal_order
+            .push("income".into());
+        let result = run_monte_carlo(&data, &test_options(0_f64, 0_f64)).unwrap();
+        assert_eq!(result.runs, 50);
+        assert_eq!(result.errors, 50);
+        assert_approx_eq!(f64, result.success_probability, 0_f64);
+        let result = run_monte_carlo(&data, &test_options(500_f64, 0_f64)).unwrap();
+        assert_eq!(result.errors, 0);
+        assert_approx_eq!(f64, result.success_probability, 100_f64);
+    }
+
+    #[test]
+    fn monte_carlo_unknown_asset_class() {
+        let mut options = test_options(5_f64, 10_f64);
+        options
+            .accounts
+            .insert("savings".into(), ReturnModel::AssetClass("bonds".into()));
+        let error = run_monte_carlo(&test_user_data(), &options).unwrap_err();
+        assert_eq!(error.uuid(), "savings");
+    }
+
+    #[test]
+    fn monte_carlo_invalid_asset_class() {
+        let options = test_options(5_f64, f64::NAN);
+        let error = run_monte_carlo(&test_user_data(), &options).unwrap_err();
+        assert!(matches!(error, SimulationError::InvalidOptions { .. }));
+        assert_eq!(error.uuid(), "");
+    }
+}
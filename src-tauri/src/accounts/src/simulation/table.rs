@@ -9,7 +9,7 @@ use ts_rs::TS;
 ///
 /// Tables are stored as keyed on string but must be converted to
 /// be keyed on a u32 year prior to use for analysis.
-#[derive(TS, Debug, Default, Clone, Deserialize, Serialize)]
+#[derive(TS, Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
 #[ts(export)]
 pub struct Table<T: std::cmp::Ord>(
     /// Ordered map of (year, dollar amount) pairs
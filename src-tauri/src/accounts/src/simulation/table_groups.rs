@@ -203,6 +203,10 @@ pub struct SavingsTables {
     pub earnings: Table<u32>,
     /// Amount of money withdrawn from the account in each year
     pub withdrawals: Table<u32>,
+    /// Portion of the account balance that has already been taxed (contributions not yet withdrawn)
+    pub cost_basis: Table<u32>,
+    /// Amount of the planned contribution that was over the contribution limit in each year
+    pub contribution_shortfall: Table<u32>,
 }
 
 impl SavingsTables {
@@ -231,8 +235,44 @@ impl SavingsTables {
                 Some(table) => table.clone(),
                 None => Table::default(),
             },
+            // The balance entered by the user is assumed to have no unrealized gains
+            cost_basis: value.clone(),
+            contribution_shortfall: Table::default(),
         }
     }
+    /// Account balance at the end of the previous year
+    pub fn prior_year_value(&self, year: u32) -> f64 {
+        self.value.get(year.saturating_sub(1)).unwrap_or_default()
+    }
+    /// Add money that has already been taxed to the cost basis
+    pub fn add_cost_basis(&mut self, year: u32, amount: f64) {
+        self.cost_basis.update(year, amount);
+    }
+    /// Cap a contribution at the limit (if there is one) and record the amount that was over it
+    pub fn limit_contribution(&mut self, year: u32, contribution: f64, limit: Option<f64>) -> f64 {
+        match limit {
+            Some(limit) if contribution > limit => {
+                let limit = limit.max(0_f64);
+                self.contribution_shortfall.update(year, contribution - limit);
+                limit
+            }
+            _ => contribution,
+        }
+    }
+    /// Remove a withdrawal from the cost basis and return the portion of it that is a capital gain
+    ///
+    /// Each dollar withdrawn is treated as the same mix of cost basis and gains as the
+    /// account balance, so this must be called before the withdrawal is removed from the value table.
+    pub fn withdraw_cost_basis(&mut self, year: u32, withdrawal: f64) -> f64 {
+        let value = self.value.get(year).unwrap_or_default();
+        let basis = self.cost_basis.get(year).unwrap_or_default();
+        if withdrawal <= 0_f64 || value <= 0_f64 {
+            return 0_f64;
+        }
+        let basis_withdrawn = withdrawal * (basis / value).clamp(0_f64, 1_f64);
+        self.cost_basis.update(year, -basis_withdrawn);
+        withdrawal - basis_withdrawn
+    }
     /// Write account values out to csv file
     pub fn write(&self, filename: String) {
         let mut years: Vec<u32> = self.value.years();
@@ -240,7 +280,7 @@ impl SavingsTables {
 
         let mut file = std::fs::File::create(filename).unwrap();
         file.write_all(
-            "year, value, contributions, employer_contributions, earnings, withdrawals\n"
+            "year, value, contributions, employer_contributions, earnings, withdrawals, cost_basis, contribution_shortfall\n"
                 .as_bytes(),
         )
         .unwrap();
@@ -248,13 +288,15 @@ impl SavingsTables {
         years.iter().for_each(|year| {
             file.write_all(
                 format!(
-                    "{}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}\n",
+                    "{}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}\n",
                     year,
                     self.value.get(*year).unwrap_or(0_f64),
                     self.contributions.get(*year).unwrap_or(0_f64),
                     self.employer_contributions.get(*year).unwrap_or(0_f64),
                     self.earnings.get(*year).unwrap_or(0_f64),
                     self.withdrawals.get(*year).unwrap_or(0_f64),
+                    self.cost_basis.get(*year).unwrap_or(0_f64),
+                    self.contribution_shortfall.get(*year).unwrap_or(0_f64),
                 )
                 .as_bytes(),
             )
@@ -286,6 +328,13 @@ impl SavingsTables {
             label: String::from("Withdrawals"),
             data: years.iter().map(|year| PlotDataPoint{x:*year, y:self.withdrawals.get(*year).unwrap_or(0_f64)}).collect()
         });
+        // Only show the shortfall for accounts that hit their contribution limit
+        if self.contribution_shortfall.values().iter().any(|value| *value > 0_f64) {
+            output.push(PlotDataSet{
+                label: String::from("Contribution Shortfall"),
+                data: years.iter().map(|year| PlotDataPoint{x:*year, y:self.contribution_shortfall.get(*year).unwrap_or(0_f64)}).collect()
+            });
+        }
 
         // years.iter().for_each(|year| {
         //     output.push(PlotDataPoint{
@@ -325,11 +374,17 @@ impl SavingsTables {
                     true => self.value.most_recent_value().unwrap_or_default(),
                     false => 0_f64,
                 };
+                let prev_basis = match pull_value_forward {
+                    true => self.cost_basis.most_recent_value().unwrap_or_default(),
+                    false => 0_f64,
+                };
                 self.value.add(year, prev_value)?;
                 self.contributions.add(year, 0_f64)?;
                 self.employer_contributions.add(year, 0_f64)?;
                 self.earnings.add(year, 0_f64)?;
                 self.withdrawals.add(year, 0_f64)?;
+                self.cost_basis.add(year, prev_basis)?;
+                self.contribution_shortfall.add(year, 0_f64)?;
                 Ok(())
             }
         }
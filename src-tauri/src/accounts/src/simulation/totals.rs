@@ -2,11 +2,14 @@
 
 use log::error;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::io::Write;
 use image::{ImageBuffer, Rgba};
 
 use super::Table;
+use crate::inputs::{LimitKind, Owner, Settings};
+use crate::AccountType;
 use crate::plot::{scatter_plot_file, scatter_plot_buf};
 
 /// How the results of the simulation of an account impact a YearlyTotal
@@ -24,8 +27,12 @@ pub struct YearlyImpact {
     pub hsa: f64,
     /// Taxable income
     pub income_taxable: f64,
+    /// Capital gains (taxed at the capital gains rate instead of as income)
+    pub capital_gains: f64,
     /// Total income (taxable + non-taxable)
     pub income: f64,
+    /// Penalties for withdrawing from accounts early (paid from net like expenses)
+    pub penalty: f64,
 }
 
 /// Set of YearlyTotal tracked over multiple years
@@ -45,12 +52,35 @@ pub struct YearlyTotals {
     pub hsa: Table<u32>,
     /// total taxable income for a year
     pub income_taxable: Table<u32>,
+    /// total capital gains for a year
+    pub capital_gains: Table<u32>,
     /// total income for a year
     pub income: Table<u32>,
     /// amount of income tax paid for a year
     pub tax_burden: Table<u32>,
+    /// income tax paid as a percent of taxable income for a year
+    pub tax_rate_effective: Table<u32>,
+    /// tax rate paid on the last dollar of taxable income for a year (percent)
+    pub tax_rate_marginal: Table<u32>,
+    /// amount of capital gains tax paid for a year
+    pub capital_gains_tax: Table<u32>,
+    /// early withdrawal penalties for a year
+    pub penalty: Table<u32>,
+    /// value of real estate less the mortgages linked to it at the end of a year
+    pub equity: Table<u32>,
+    /// total value of all accounts that are assets at the end of a year
+    pub assets: Table<u32>,
+    /// total balance owed on all loans and mortgages at the end of a year
+    pub liabilities: Table<u32>,
+    /// net plus assets less liabilities at the end of a year
+    pub net_worth: Table<u32>,
+    /// balance sheet value of the accounts of each type at the end of a year (liabilities are negative)
+    pub balance_sheet: BTreeMap<AccountType, Table<u32>>,
     /// currently unused
     pub income_during_retirement: Table<u32>,
+    /// contributions each owner made toward the limit for each kind of account in a year
+    #[serde(skip)]
+    pub limited_contributions: HashMap<(Owner, LimitKind), Table<u32>>,
 }
 
 impl YearlyTotals {
@@ -72,8 +102,17 @@ impl YearlyTotals {
                 self.saving.insert(year, 0_f64);
                 self.hsa.insert(year, 0_f64);
                 self.income_taxable.insert(year, 0_f64);
+                self.capital_gains.insert(year, 0_f64);
                 self.income.insert(year, 0_f64);
                 self.tax_burden.insert(year, 0_f64);
+                self.tax_rate_effective.insert(year, 0_f64);
+                self.tax_rate_marginal.insert(year, 0_f64);
+                self.capital_gains_tax.insert(year, 0_f64);
+                self.penalty.insert(year, 0_f64);
+                self.equity.insert(year, 0_f64);
+                self.assets.insert(year, 0_f64);
+                self.liabilities.insert(year, 0_f64);
+                self.net_worth.insert(year, 0_f64);
                 self.income_during_retirement.insert(year, 0_f64);
                 if pull_value_forward {
                     self.pull_value_forward(year);
@@ -105,7 +144,9 @@ impl YearlyTotals {
                 self.saving.update(year, update.saving);
                 self.hsa.update(year, update.hsa);
                 self.income_taxable.update(year, update.income_taxable);
+                self.capital_gains.update(year, update.capital_gains);
                 self.income.update(year, update.income);
+                self.penalty.update(year, update.penalty);
             }
             false => {
                 error!("Updating a year that does not exist.  Previous values not pulled forward");
@@ -120,19 +161,44 @@ impl YearlyTotals {
         self.net.update(year, self.income.get(year).unwrap());
     }
     /// Pay income tax for the year
-    pub fn pay_income_tax_from_net(&mut self, year: u32, tax_rate: f64) {
-        let tax_burden = self.income_taxable.get(year).unwrap() * (tax_rate / 100_f64);
+    ///
+    /// Any income tax already paid for the year is refunded first so this can be called
+    /// again when taxable income changes after the year is closed out.
+    pub fn pay_income_tax_from_net(&mut self, year: u32, settings: &Settings) {
+        self.net.update(year, self.tax_burden.get(year).unwrap_or_default());
+        let income_tax = settings.income_tax(self.income_taxable.get(year).unwrap(), year);
         // log what income was after paying taxes
-        self.tax_burden.insert(year, tax_burden);
+        self.tax_burden.insert(year, income_tax.tax);
+        self.tax_rate_effective.insert(year, income_tax.effective_rate);
+        self.tax_rate_marginal.insert(year, income_tax.marginal_rate);
 
         // take income tax payment out of net
-        self.net.update(year, -1_f64 * tax_burden);
+        self.net.update(year, -1_f64 * income_tax.tax);
+    }
+    /// Pay capital gains tax for the year
+    ///
+    /// Net capital losses are not deducted from other income so no tax is paid when
+    /// capital gains for the year are negative.  Any capital gains tax already paid for
+    /// the year is refunded first.
+    pub fn pay_capital_gains_tax_from_net(&mut self, year: u32, settings: &Settings) {
+        self.net.update(year, self.capital_gains_tax.get(year).unwrap_or_default());
+        let capital_gains_tax = self.capital_gains.get(year).unwrap().max(0_f64)
+            * (settings.tax_capital_gains / 100_f64);
+        self.capital_gains_tax.insert(year, capital_gains_tax);
+
+        // take capital gains tax payment out of net
+        self.net.update(year, -capital_gains_tax);
     }
     /// Pay for expenses for the year
     pub fn pay_expenses_from_net(&mut self, year: u32) {
         self.net
             .update(year, -1_f64 * self.expense.get(year).unwrap());
     }
+    /// Pay early withdrawal penalties for the year
+    pub fn pay_penalties_from_net(&mut self, year: u32) {
+        self.net
+            .update(year, -self.penalty.get(year).unwrap_or_default());
+    }
     /// Remove healthcare expenses from net (these could also be covered by HSA accounts)
     pub fn pay_healthcare_expenses_from_net(&mut self, year: u32) {
         if self.healthcare_expense.get(year).unwrap() > 0_f64 {
@@ -141,15 +207,41 @@ impl YearlyTotals {
             self.healthcare_expense.insert(year, 0_f64);
         }
     }
+    /// Record the balance sheet and home equity at the end of the year
+    ///
+    /// `values` holds the balance sheet value of every account on the balance sheet (assets
+    /// are positive and liabilities are negative).
+    pub fn record_balance_sheet(&mut self, year: u32, values: &[(AccountType, f64)], equity: f64) {
+        let assets: f64 = values.iter().map(|(_, value)| value.max(0_f64)).sum();
+        let liabilities: f64 = values.iter().map(|(_, value)| -value.min(0_f64)).sum();
+        values.iter().for_each(|(account_type, value)| {
+            self.balance_sheet
+                .entry(*account_type)
+                .or_default()
+                .update(year, *value);
+        });
+        self.equity.insert(year, equity);
+        self.assets.insert(year, assets);
+        self.liabilities.insert(year, liabilities);
+        self.net_worth.insert(
+            year,
+            self.net.get(year).unwrap_or_default() + assets - liabilities,
+        );
+    }
     /// Write yearly total data to a csv file
     pub fn write_summary(&self, filename: String) {
         let mut file = std::fs::File::create(filename).unwrap();
-        file.write_all("year, totals.net, totals.saving, totals.hsa, totals.healthcare_expense, totals.expense, totals.col, totals.income, totals.income_taxable, totals.tax_burden\n".as_bytes()).unwrap();
+        file.write_all("year, totals.net, totals.saving, totals.hsa, totals.healthcare_expense, totals.expense, totals.col, totals.income, totals.income_taxable, totals.tax_burden, totals.tax_rate_effective, totals.tax_rate_marginal, totals.capital_gains, totals.capital_gains_tax, totals.penalty, totals.equity, totals.assets, totals.liabilities, totals.net_worth".as_bytes()).unwrap();
+        self.balance_sheet.keys().for_each(|account_type| {
+            let header = format!(", totals.balance_sheet.{}", account_type.to_string());
+            file.write_all(header.as_bytes()).unwrap();
+        });
+        file.write_all("\n".as_bytes()).unwrap();
 
         self.years().iter().for_each(|year| {
             file.write_all(
                 format!(
-                    "{},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2}\n",
+                    "{},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2},\t{:.2}",
                     year,
                     self.net.get(*year).unwrap_or_default(),
                     self.saving.get(*year).unwrap_or_default(),
@@ -159,11 +251,27 @@ impl YearlyTotals {
                     self.col.get(*year).unwrap_or_default(),
                     self.income.get(*year).unwrap_or_default(),
                     self.income_taxable.get(*year).unwrap_or_default(),
-                    self.tax_burden.get(*year).unwrap_or_default()
+                    self.tax_burden.get(*year).unwrap_or_default(),
+                    self.tax_rate_effective.get(*year).unwrap_or_default(),
+                    self.tax_rate_marginal.get(*year).unwrap_or_default(),
+                    self.capital_gains.get(*year).unwrap_or_default(),
+                    self.capital_gains_tax.get(*year).unwrap_or_default(),
+                    self.penalty.get(*year).unwrap_or_default(),
+                    self.equity.get(*year).unwrap_or_default(),
+                    self.assets.get(*year).unwrap_or_default(),
+                    self.liabilities.get(*year).unwrap_or_default(),
+                    self.net_worth.get(*year).unwrap_or_default()
                 )
                 .as_bytes(),
             )
             .unwrap();
+            self.balance_sheet.values().for_each(|table| {
+                file.write_all(
+                    format!(",\t{:.2}", table.get(*year).unwrap_or_default()).as_bytes(),
+                )
+                .unwrap();
+            });
+            file.write_all("\n".as_bytes()).unwrap();
         });
     }
     /// Generate plot
@@ -177,6 +285,12 @@ impl YearlyTotals {
         let income: Vec<f64> = self.income.values();
         let income_taxable: Vec<f64> = self.income_taxable.values();
         let tax_burden: Vec<f64> = self.tax_burden.values();
+        let capital_gains: Vec<f64> = self.capital_gains.values();
+        let capital_gains_tax: Vec<f64> = self.capital_gains_tax.values();
+        let equity: Vec<f64> = self.equity.values();
+        let assets: Vec<f64> = self.assets.values();
+        let liabilities: Vec<f64> = self.liabilities.values();
+        let net_worth: Vec<f64> = self.net_worth.values();
 
         scatter_plot_file(
             filepath,
@@ -196,6 +310,15 @@ impl YearlyTotals {
                     &(self.years(), income_taxable).into(),
                 ),
                 ("Tax Burden".into(), &(self.years(), tax_burden).into()),
+                ("Capital Gains".into(), &(self.years(), capital_gains).into()),
+                (
+                    "Capital Gains Tax".into(),
+                    &(self.years(), capital_gains_tax).into(),
+                ),
+                ("Equity".into(), &(self.years(), equity).into()),
+                ("Assets".into(), &(self.years(), assets).into()),
+                ("Liabilities".into(), &(self.years(), liabilities).into()),
+                ("Net Worth".into(), &(self.years(), net_worth).into()),
             ],
             "Summary".into(),
             1600,
@@ -213,6 +336,12 @@ impl YearlyTotals {
         let income: Vec<f64> = self.income.values();
         let income_taxable: Vec<f64> = self.income_taxable.values();
         let tax_burden: Vec<f64> = self.tax_burden.values();
+        let capital_gains: Vec<f64> = self.capital_gains.values();
+        let capital_gains_tax: Vec<f64> = self.capital_gains_tax.values();
+        let equity: Vec<f64> = self.equity.values();
+        let assets: Vec<f64> = self.assets.values();
+        let liabilities: Vec<f64> = self.liabilities.values();
+        let net_worth: Vec<f64> = self.net_worth.values();
         scatter_plot_buf(
             vec![
                 ("Net".into(), &(self.years(), net).into()),
@@ -230,6 +359,15 @@ impl YearlyTotals {
                     &(self.years(), income_taxable).into(),
                 ),
                 ("Tax Burden".into(), &(self.years(), tax_burden).into()),
+                ("Capital Gains".into(), &(self.years(), capital_gains).into()),
+                (
+                    "Capital Gains Tax".into(),
+                    &(self.years(), capital_gains_tax).into(),
+                ),
+                ("Equity".into(), &(self.years(), equity).into()),
+                ("Assets".into(), &(self.years(), assets).into()),
+                ("Liabilities".into(), &(self.years(), liabilities).into()),
+                ("Net Worth".into(), &(self.years(), net_worth).into()),
             ],
             "Summary".into(),
             width,
@@ -256,12 +394,46 @@ impl YearlyTotals {
     pub fn get_saving(&self, year: u32) -> f64 {
         self.saving.get(year).unwrap_or_default()
     }
+    /// Get the contributions an owner made toward the limit for a kind of account in a year
+    ///
+    /// If the year is not found then zero is returned
+    pub fn get_limited_contribution(&self, owner: Owner, kind: LimitKind, year: u32) -> f64 {
+        self.limited_contributions
+            .get(&(owner, kind))
+            .and_then(|table| table.get(year))
+            .unwrap_or_default()
+    }
+    /// Count a contribution toward the limit an owner has for a kind of account in a year
+    pub fn add_limited_contribution(
+        &mut self,
+        owner: Owner,
+        kind: LimitKind,
+        year: u32,
+        amount: f64,
+    ) {
+        self.limited_contributions
+            .entry((owner, kind))
+            .or_default()
+            .update(year, amount);
+    }
     /// Get the healthcare_expense for the specified year
     ///
     /// If the year is not found then zero is returned
     pub fn get_healthcare_expense(&self, year: u32) -> f64 {
         self.healthcare_expense.get(year).unwrap_or_default()
     }
+    /// Get the total income and capital gains tax paid over every year
+    pub fn lifetime_taxes(&self) -> f64 {
+        self.tax_burden.values().iter().sum::<f64>()
+            + self.capital_gains_tax.values().iter().sum::<f64>()
+    }
+    /// Years that early withdrawal penalties were paid in
+    pub fn penalty_years(&self) -> Vec<u32> {
+        self.years()
+            .into_iter()
+            .filter(|year| self.penalty.get(*year).unwrap_or_default() > 0_f64)
+            .collect()
+    }
     /// Return a sorted list of keys (years)
     ///
     /// There should not be a way for the elements of self to contain
@@ -5,8 +5,15 @@ use std::error::Error;
 use ts_rs::TS;
 use image::{ImageBuffer, Rgba};
 
+use crate::inputs::fixed_with_inflation;
+
 use super::*;
 
+/// Full retirement age in months for people born in or after 1960
+const FULL_RETIREMENT_AGE_MONTHS: u32 = 67 * 12;
+/// Latest age in months that delayed retirement credits are earned
+const MAX_DELAY_AGE_MONTHS: u32 = 70 * 12;
+
 /// Social Security Account
 #[derive(TS, Debug, Clone, Deserialize, Serialize)]
 #[ts(export)]
@@ -14,12 +21,16 @@ use super::*;
 pub struct Ssa {
     /// String describing this account
     name: String,
-    /// Base income from social security
+    /// Yearly benefit from social security when claimed at full retirement age [in today's dollars]
     base: f64,
-    /// Calendar year when money starts being earned by this account
+    /// Calendar year when money starts being earned by this account (the year benefits are claimed)
     start_in: YearInput,
     /// Calendar year when money stops being earned by this account
     end_in: YearInput,
+    /// Person in the household the account belongs to (you when not set)
+    owner: Option<Owner>,
+    /// Social Security account of your spouse (used for survivor benefits)
+    spouse_link: Option<String>,
     /// General information to store with this account
     notes: Option<String>,
     // The following items are used when running the program and are not stored with the user data
@@ -36,7 +47,10 @@ impl Account for Ssa {
         AccountType::Ssa
     }
     fn link_id(&self) -> Option<String> {
-        None
+        self.spouse_link.clone()
+    }
+    fn owner(&self) -> Owner {
+        self.owner.unwrap_or_default()
     }
     fn name(&self) -> String {
         self.name.clone()
@@ -46,19 +60,26 @@ impl Account for Ssa {
         linked_dates: Option<Dates>,
         settings: &Settings,
     ) -> Result<Vec<(u32, YearlyImpact)>, Box<dyn Error>> {
-        if linked_dates.is_some() {
+        if linked_dates.is_some() && self.spouse_link.is_none() {
             return Err(String::from("Linked account dates provided but not used").into());
         }
         self.analysis = SingleTable::default();
+        // The linked spouse account only provides a benefit amount so its dates are not used
         self.dates = Dates {
-            year_in: self.get_range_in(settings, linked_dates),
-            year_out: self.get_range_out(settings, linked_dates),
+            year_in: self.get_range_in(settings, None),
+            year_out: self.get_range_out(settings, None),
         };
         Ok(Vec::new())
     }
     fn get_value(&self, year: u32) -> Option<f64> {
         self.analysis.value.get(year)
     }
+    /// Benefit the owner is entitled to in a year, even after they have died
+    ///
+    /// Accounts that link to this one (the spouse's account) use it to compute survivor benefits.
+    fn linked_value(&self, year: u32, settings: &Settings) -> Option<f64> {
+        Some(self.benefit(year, settings))
+    }
     fn get_range_in(&self, settings: &Settings, linked_dates: Option<Dates>) -> Option<YearRange> {
         Some(YearRange {
             start: self
@@ -104,24 +125,234 @@ impl Account for Ssa {
     fn simulate(
         &mut self,
         year: u32,
-        _totals: &YearlyTotals,
-        _settings: &Settings,
+        linked_value: Option<f64>,
+        totals: &YearlyTotals,
+        settings: &Settings,
     ) -> Result<YearlyImpact, Box<dyn Error>> {
-        let mut _result = WorkingValues::default();
+        let mut result = WorkingValues::default();
 
         self.analysis.add_year(year, false)?;
 
+        // Calculate benefit (paid while the owner is alive)
+        if self.dates.year_in.unwrap().contains(year) && settings.is_alive(self.owner(), year) {
+            result.earning = self.benefit(year, settings);
+
+            // A survivor receives the larger of their own benefit or the benefit of their late spouse
+            if let (true, Some(spouse_benefit)) =
+                (settings.is_survivor(self.owner(), year), linked_value)
+            {
+                result.earning = result.earning.max(spouse_benefit);
+            }
+        }
+
+        // Add benefit to value table
+        self.analysis.value.update(year, result.earning);
+
+        // Combined income is half of the benefit plus all other income for the year
+        let combined_income = totals.get_income(year) + result.earning / 2_f64;
+
         Ok(YearlyImpact {
             expense: 0_f64,
             healthcare_expense: 0_f64,
             col: 0_f64,
             saving: 0_f64,
-            income_taxable: 0_f64,
-            income: 0_f64,
+            income_taxable: result.earning * taxable_fraction(combined_income, settings),
+            capital_gains: 0_f64,
+            income: result.earning,
             hsa: 0_f64,
+            penalty: 0_f64,
         })
     }
     fn write(&self, filepath: String) {
         self.analysis.write(filepath);
     }
 }
+
+impl Ssa {
+    /// Yearly benefit based on when it is claimed (zero before it is claimed)
+    fn benefit(&self, year: u32, settings: &Settings) -> f64 {
+        let claim_year = self.dates.year_in.unwrap_or_default().start;
+        if year < claim_year {
+            return 0_f64;
+        }
+        let year_born = settings.year_born_of(self.owner());
+        let claim_age = claim_year.saturating_sub(year_born);
+        fixed_with_inflation(self.base, year, settings) * claiming_adjustment(claim_age, year_born)
+    }
+}
+
+/// Full retirement age in months based on the year of birth
+fn full_retirement_age_months(year_born: u32) -> u32 {
+    match year_born {
+        0..=1937 => 65 * 12,
+        1938..=1942 => 65 * 12 + (year_born - 1937) * 2,
+        1943..=1954 => 66 * 12,
+        1955..=1959 => 66 * 12 + (year_born - 1954) * 2,
+        _ => FULL_RETIREMENT_AGE_MONTHS,
+    }
+}
+
+/// Fraction of the full retirement age benefit received when claiming at `claim_age`
+///
+/// Claiming early reduces the benefit by 5/9 of 1% per month for the first 36 months and
+/// 5/12 of 1% per month beyond that.  Delaying past full retirement age increases the benefit
+/// by 2/3 of 1% per month up to age 70.
+fn claiming_adjustment(claim_age: u32, year_born: u32) -> f64 {
+    let full_retirement_age = full_retirement_age_months(year_born);
+    let claim_age = (claim_age * 12).min(MAX_DELAY_AGE_MONTHS);
+    if claim_age < full_retirement_age {
+        let months_early = (full_retirement_age - claim_age) as f64;
+        let first_months = months_early.min(36_f64);
+        let extra_months = months_early - first_months;
+        1_f64 - first_months * 5_f64 / 900_f64 - extra_months * 5_f64 / 1200_f64
+    } else {
+        let months_late = (claim_age - full_retirement_age) as f64;
+        1_f64 + months_late * 2_f64 / 300_f64
+    }
+}
+
+/// Fraction of the benefit that is taxable for a given combined income
+///
+/// No benefit is taxable up to the low breakpoint.  Between the breakpoints the taxable
+/// percentage is interpolated from the low to the high taxable income percentage.  Above the
+/// high breakpoint the high taxable income percentage is used.
+///
+/// The taxable percentage steps from zero to the low taxable income percentage as soon as
+/// combined income goes over the low breakpoint.  This is intended since the settings give the
+/// percentage that is taxable at each breakpoint rather than a rate that phases in.
+fn taxable_fraction(combined_income: f64, settings: &Settings) -> f64 {
+    let breakpoints = &settings.ssa.breakpoints;
+    let percentage = &settings.ssa.taxable_income_percentage;
+    let taxable_percentage = if combined_income <= breakpoints.low {
+        0_f64
+    } else if combined_income >= breakpoints.high {
+        percentage.high
+    } else {
+        percentage.low
+            + (percentage.high - percentage.low) * (combined_income - breakpoints.low)
+                / (breakpoints.high - breakpoints.low)
+    };
+    taxable_percentage / 100_f64
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use crate::inputs::{Settings, Span, Spouse, SsaSettings};
+    use super::*;
+
+    fn test_settings_values() -> Settings {
+        Settings {
+            age_retire: 50,
+            age_die: 100,
+            year_born: 1980,
+            year_start: 2000,
+            inflation_base: 5.0,
+            tax_income: 20.0,
+            tax_capital_gains: 10.0,
+            retirement_cost_of_living: 80.0,
+            ssa: SsaSettings {
+                breakpoints: Span {
+                    low: 30000_f64,
+                    high: 40000_f64,
+                },
+                taxable_income_percentage: Span {
+                    low: 50_f64,
+                    high: 80_f64,
+                },
+            },
+            spouse: None,
+            tax_brackets: None,
+            cash_sweep: None,
+            drawdown: None,
+            roth_conversion: None,
+            contribution_limits: None,
+            early_withdrawal_penalties: None,
+            inflation_schedule: None,
+            inflation_sequence: None,
+        }
+    }
+
+    fn test_account() -> Ssa {
+        Ssa {
+            name: "Social Security".into(),
+            base: 20_000_f64,
+            start_in: YearInput::ConstantInt(2047),
+            end_in: YearInput::ConstantInt(2080),
+            owner: None,
+            spouse_link: None,
+            notes: None,
+            analysis: SingleTable::default(),
+            dates: Dates::default(),
+        }
+    }
+
+    #[test]
+    fn claiming_age() {
+        assert_approx_eq!(f64, claiming_adjustment(67, 1980), 1_f64);
+        assert_approx_eq!(f64, claiming_adjustment(62, 1980), 0.7_f64, epsilon = 0.0001);
+        assert_approx_eq!(f64, claiming_adjustment(70, 1980), 1.24_f64, epsilon = 0.0001);
+        assert_approx_eq!(f64, claiming_adjustment(72, 1980), 1.24_f64, epsilon = 0.0001);
+        assert_approx_eq!(f64, claiming_adjustment(66, 1950), 1_f64);
+    }
+
+    #[test]
+    fn taxable_portion() {
+        let settings = test_settings_values();
+        assert_approx_eq!(f64, taxable_fraction(20_000_f64, &settings), 0_f64);
+        // the low percentage applies as soon as combined income goes over the low breakpoint
+        assert_approx_eq!(f64, taxable_fraction(30_000_f64, &settings), 0_f64);
+        assert_approx_eq!(f64, taxable_fraction(30_001_f64, &settings), 0.5_f64, epsilon = 1e-4);
+        assert_approx_eq!(f64, taxable_fraction(35_000_f64, &settings), 0.65_f64);
+        assert_approx_eq!(f64, taxable_fraction(50_000_f64, &settings), 0.8_f64);
+    }
+
+    #[test]
+    fn ssa_simulation() {
+        let settings = test_settings_values();
+        let yearly_totals = YearlyTotals::new();
+        let mut account = test_account();
+        account.init(None, &settings).unwrap();
+
+        // no benefit before claiming
+        let update = account.simulate(2046, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, 0_f64);
+
+        // claimed at full retirement age so the benefit is the base adjusted for inflation
+        let update = account.simulate(2047, None, &yearly_totals, &settings).unwrap();
+        let benefit = fixed_with_inflation(20_000_f64, 2047, &settings);
+        assert_approx_eq!(f64, update.income, benefit);
+        assert_approx_eq!(f64, update.income_taxable, benefit * 0.8_f64);
+        assert_approx_eq!(f64, account.get_value(2047).unwrap(), benefit);
+    }
+
+    #[test]
+    fn survivor_benefit() {
+        let mut settings = test_settings_values();
+        settings.spouse = Some(Spouse {
+            age_retire: 60,
+            age_die: 70,
+            year_born: 1980,
+        });
+        let yearly_totals = YearlyTotals::new();
+        let mut account = test_account();
+        account.spouse_link = Some("spouse".into());
+        account.init(None, &settings).unwrap();
+        let mut spouse_account = test_account();
+        spouse_account.base = 30_000_f64;
+        spouse_account.owner = Some(Owner::Spouse);
+        spouse_account.init(None, &settings).unwrap();
+
+        // while both are alive each gets their own benefit
+        let spouse_benefit = spouse_account.linked_value(2049, &settings);
+        let update = account.simulate(2049, spouse_benefit, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, fixed_with_inflation(20_000_f64, 2049, &settings));
+
+        // after the spouse dies their benefit stops and the survivor gets the larger benefit
+        let update = spouse_account.simulate(2050, None, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, 0_f64);
+        let spouse_benefit = spouse_account.linked_value(2050, &settings);
+        let update = account.simulate(2050, spouse_benefit, &yearly_totals, &settings).unwrap();
+        assert_approx_eq!(f64, update.income, fixed_with_inflation(30_000_f64, 2050, &settings));
+    }
+}
@@ -8,16 +8,17 @@
     windows_subsystem = "windows"
   )]
 
-use std::collections::HashMap;
-
-use log::{info, trace, LevelFilter};
+use log::{info, LevelFilter};
 use std::fs::read_to_string;
 use serde::{Deserialize, Serialize};
 
 mod menu;
 mod logconfig;
 
-use accounts::{Account, AccountWrapper, Dates, UserData, YearlyTotals, PlotDataSet};
+use accounts::{
+  Account, AccountWrapper, AnalysisResults, BacktestResult, ConversionComparison,
+  MonteCarloOptions, MonteCarloResult, SimulationError, UserData,
+};
 
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -68,120 +69,34 @@ fn file_save(path: String, data: UserData<AccountWrapper> ) -> Result<String, St
 }
 
 #[tauri::command]
-fn run_analysis(input: UserData<AccountWrapper>) -> (HashMap<String, Vec<PlotDataSet>>, YearlyTotals) {
+fn run_analysis(input: UserData<AccountWrapper>) -> Result<AnalysisResults, SimulationError> {
   let data : UserData<Box<dyn Account>> = input.into();
-  analyze(data)
+  Ok(accounts::run(data)?.analysis_results())
 }
 
 #[tauri::command]
-fn do_a_thing(body: RequestBody) -> String {
-  println!("{:?}", body);
-  format!("{:?}", body)
-  // "message response".into()
+fn run_monte_carlo(input: UserData<AccountWrapper>, options: MonteCarloOptions) -> Result<MonteCarloResult, SimulationError> {
+  accounts::run_monte_carlo(&input, &options)
 }
 
-fn analyze(mut data: UserData<Box<dyn Account>>) -> (HashMap<String, Vec<PlotDataSet>>, YearlyTotals) {
-    // Loop through accounts to determine what order they should be processed in
-    let mut account_order: Vec<String> = Vec::new();
-
-    for type_id in AccountWrapper::order().iter() {
-        for (uuid, account) in data.accounts.iter() {
-            if account.type_id() == *type_id {
-                account_order.push(uuid.to_string());
-            }
-        }
-    }
-
-    // Initialize vector of year values
-    let years: Vec<u32> =
-        (data.settings.year_start()..data.settings.year_end()).collect::<Vec<u32>>();
-
-    // Initilize object to keep track of yearly totals across all accounts
-    let mut yearly_totals = YearlyTotals::new();
-
-    // Initialize accounts
-    account_order.iter().for_each(|uuid| {
-        // Get dates from the linked account if this account has a link ID
-        let linked_dates: Option<Dates> = match data.accounts.get(uuid).unwrap().link_id() {
-            Some(link_id) => {
-                // This explicitly does not allow recursion in linked_dates
-                Some(Dates {
-                    year_in: data
-                        .accounts
-                        .get(&link_id)
-                        .unwrap()
-                        .get_range_in(&data.settings, None),
-                    year_out: data
-                        .accounts
-                        .get(&link_id)
-                        .unwrap()
-                        .get_range_out(&data.settings, None),
-                })
-            }
-            None => None,
-        };
-
-        // Initialize the account & get the impacts it has based on the tables of historical data the user has input
-        let impacts = data
-            .accounts
-            .get_mut(uuid)
-            .unwrap()
-            .init(linked_dates, &data.settings)
-            .unwrap();
-
-        // Apply the impacts to yearly totals
-        impacts.iter().for_each(|(year, impact)| {
-            if !yearly_totals.contains_year(*year) {
-                yearly_totals.add_year(*year, false).unwrap();
-            }
-            yearly_totals.update(*year, *impact);
-        });
-
-        trace!(
-            "{:?} {:?} {:?}",
-            data.accounts.get(uuid).unwrap().type_id(),
-            uuid,
-            data.accounts.get(uuid).unwrap().name(),
-        );
-    });
-
-    info!("Main Loop");
-
-    // Main loop to loop through each year
-    years.iter().copied().for_each(|year| {
-        // Add a new year to yearly_totals and pull some of the previous values forward
-        // If the year already exists (as it might if a user has historical data that
-        // conflicts with this analysis year) then skip analysis and leave the yearly total
-        // tables as they are.
-        if yearly_totals.add_year(year, true).is_ok() {
-            // Loop through accounts to make contributions and withdrawals
-            account_order.iter().for_each(|uuid| {
-                // Simulate this year for the account with specified uuid
-                let account = data.accounts.get_mut(uuid).unwrap();
-                let impact = account
-                    .simulate(year, &yearly_totals, &data.settings)
-                    .unwrap();
-                // Apply the impact for this account to yearly_totals
-                yearly_totals.update(year, impact);
-            });
-
-            // Close out the year
-            yearly_totals.deposit_income_in_net(year);
-            yearly_totals.pay_income_tax_from_net(year, data.settings.tax_income);
-            yearly_totals.pay_expenses_from_net(year);
-            yearly_totals.pay_healthcare_expenses_from_net(year);
-        }
-    });
-
-    let mut plot_data : HashMap<String, Vec<PlotDataSet>> = HashMap::new();
-
-    for (uuid, account) in data.accounts.iter() {
-        plot_data.insert(uuid.to_string(), account.get_plot_data());
-    }
+#[tauri::command]
+fn run_backtest(input: UserData<AccountWrapper>) -> Result<BacktestResult, SimulationError> {
+  accounts::run_backtest(&input)
+}
 
-    (plot_data, yearly_totals)
+#[tauri::command]
+fn compare_roth_conversion(
+  input: UserData<AccountWrapper>,
+) -> Result<ConversionComparison, SimulationError> {
+  accounts::run_conversion_comparison(&input)
 }
 
+#[tauri::command]
+fn do_a_thing(body: RequestBody) -> String {
+  println!("{:?}", body);
+  format!("{:?}", body)
+  // "message response".into()
+}
 
 /// Main loop
 fn main() {
@@ -226,6 +141,9 @@ fn main() {
             file_open,
             file_save,
             run_analysis,
+            run_monte_carlo,
+            run_backtest,
+            compare_roth_conversion,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -241,6 +159,7 @@ fn initialize_logger(log_level: &str) {
         .expect("Unable to parse log level");
     env_logger::builder()
         .filter(Some("fpapp"), log_level)
+        .filter(Some("accounts"), log_level)
         .init();
     info!("Initializing...");
 }
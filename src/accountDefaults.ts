@@ -19,6 +19,17 @@ export let defaultCollege: Account ={
     taxStatus: "contribute_taxed_earnings_untaxed_when_used",
     notes: ""
 }
+export let defaultEvent: Account ={
+    type: "event",
+    name: "",
+    table: {},
+    year: 0,
+    amount: 0,
+    taxTreatment: "untaxed",
+    costBasis: null,
+    destination: null,
+    notes: ""
+}
 export let defaultExpense: Account ={
     type: "expense",
     name: "",
@@ -46,6 +57,7 @@ export let defaultHsa: Account ={
     withdrawalType: "fixed",
     withdrawalValue: 0,
     taxStatus: "contribute_taxed_earnings_untaxed_when_used",
+    owner: "primary",
     notes: ""
 }
 export let defaultIncome: Account ={
@@ -56,6 +68,7 @@ export let defaultIncome: Account ={
     startIn: 0,
     endIn: 0,
     raise: "",
+    owner: "primary",
     notes: ""
 }
 export let defaultLoan: Account ={
@@ -66,7 +79,9 @@ export let defaultLoan: Account ={
     endOut: 0,
     paymentType: "fixed",
     paymentValue: 0,
+    term: null,
     rate: "",
+    changes: null,
     notes: ""
 }
 export let defaultMortgage: Account ={
@@ -77,12 +92,37 @@ export let defaultMortgage: Account ={
     endOut: 0,
     paymentType: "fixed",
     paymentValue: 0,
+    term: null,
     rate: "",
+    changes: null,
     compoundTime: 0,
     mortgageInsurance: 0,
     ltvLimit: 0,
     escrowValue: 0,
+    escrow: null,
     homeValue: 0,
+    propertyLink: null,
+    amortization: null,
+    notes: ""
+}
+export let defaultPension: Account ={
+    type: "pension",
+    name: "",
+    table: {},
+    startIn: 0,
+    benefit: 0,
+    cola: null,
+    survivorPercent: 0,
+    lumpSum: null,
+    owner: "primary",
+    notes: ""
+}
+export let defaultRealEstate: Account ={
+    type: "realEstate",
+    name: "",
+    table: {},
+    purchasePrice: 0,
+    appreciation: "",
     notes: ""
 }
 export let defaultRetirement: Account ={
@@ -105,6 +145,8 @@ export let defaultRetirement: Account ={
     taxStatus: "contribute_taxed_earnings_untaxed_when_used",
     incomeLink: "",
     matching: {},
+    limitKind: null,
+    owner: "primary",
     notes: ""
 }
 export let defaultSavings: Account ={
@@ -124,6 +166,7 @@ export let defaultSavings: Account ={
     withdrawalType: "fixed",
     withdrawalValue: 0,
     taxStatus: "contribute_taxed_earnings_untaxed_when_used",
+    owner: "primary",
     notes: ""
 }
 export let defaultSsa: Account ={
@@ -132,5 +175,6 @@ export let defaultSsa: Account ={
     base: 0,
     startIn: 0,
     endIn: 0,
+    owner: "primary",
     notes: ""
 }
\ No newline at end of file
@@ -5,8 +5,14 @@ import { v4 as uuid } from 'uuid';
 import type { AccountWrapperUI as Account } from "../src-tauri/src/accounts/bindings/AccountWrapperUI";
 
 import type {Settings} from "../src-tauri/src/accounts/bindings/Settings";
+import type {SimulationError} from "../src-tauri/src/accounts/bindings/SimulationError";
+import type {MonteCarloOptions} from "../src-tauri/src/accounts/bindings/MonteCarloOptions";
+import type {MonteCarloResult} from "../src-tauri/src/accounts/bindings/MonteCarloResult";
+import type {BacktestResult} from "../src-tauri/src/accounts/bindings/BacktestResult";
+import type {ConversionComparison} from "../src-tauri/src/accounts/bindings/ConversionComparison";
+import type {AmortizationSummary} from "../src-tauri/src/accounts/bindings/AmortizationSummary";
 
-import {defaultCollege, defaultExpense, defaultHsa, defaultIncome, defaultLoan, defaultMortgage, defaultRetirement, defaultSavings, defaultSsa} from "./accountDefaults";
+import {defaultCollege, defaultEvent, defaultExpense, defaultHsa, defaultIncome, defaultLoan, defaultMortgage, defaultPension, defaultRealEstate, defaultRetirement, defaultSavings, defaultSsa} from "./accountDefaults";
 
 // import type { College } from "../src-tauri/src/accounts/bindings/College";
 
@@ -99,9 +105,59 @@ function run_analysis(inputs) {
         input: {...inputs},
     })
     .then((results) => {
-        plot_data.set(results[0])
-        summary_data.set(results[1])
+        plot_data.set(results.plotData)
+        summary_data.set(results.totals)
+        penalty_years.set(results.penaltyYears)
+        amortization_summaries.set(results.amortization)
+        analysis_error.reset()
         console.log(results);
+    })
+    .catch((error: SimulationError) => {
+        analysis_error.set(error)
+        console.error(error);
+    });
+}
+
+export function run_monte_carlo(inputs: InputData, options: MonteCarloOptions) {
+    invoke("run_monte_carlo", {
+        input: {...inputs},
+        options: {...options},
+    })
+    .then((results: MonteCarloResult) => {
+        monte_carlo_data.set(results)
+        analysis_error.reset()
+    })
+    .catch((error: SimulationError) => {
+        analysis_error.set(error)
+        console.error(error);
+    });
+}
+
+export function run_backtest(inputs: InputData) {
+    invoke("run_backtest", {
+        input: {...inputs},
+    })
+    .then((results: BacktestResult) => {
+        backtest_data.set(results)
+        analysis_error.reset()
+    })
+    .catch((error: SimulationError) => {
+        analysis_error.set(error)
+        console.error(error);
+    });
+}
+
+export function compare_roth_conversion(inputs: InputData) {
+    invoke("compare_roth_conversion", {
+        input: {...inputs},
+    })
+    .then((results: ConversionComparison) => {
+        conversion_data.set(results)
+        analysis_error.reset()
+    })
+    .catch((error: SimulationError) => {
+        analysis_error.set(error)
+        console.error(error);
     });
 }
 
@@ -133,17 +189,28 @@ let defaultInput : InputData = {
             breakpoints: {low:0,high:0},
             taxableIncomePercentage: {low:0,high:0},
         },
+        spouse: null,
+        taxBrackets: null,
+        cashSweep: null,
+        drawdown: null,
+        rothConversion: null,
+        contributionLimits: null,
+        earlyWithdrawalPenalties: null,
+        inflationSchedule: null,
     },
 };
 
 
 export enum AccountType {
     college,
+    event,
     expense,
     hsa,
     income,
     loan,
     mortgage,
+    pension,
+    realEstate,
     retirement,
     savings,
     ssa
@@ -186,6 +253,9 @@ function createInput() {
                     case AccountType.college :
                         current.accounts[id] = JSON.parse(JSON.stringify(defaultCollege));
                         break;
+                    case AccountType.event:
+                        current.accounts[id] = JSON.parse(JSON.stringify(defaultEvent));
+                        break;
                     case AccountType.expense:
                         current.accounts[id] = JSON.parse(JSON.stringify(defaultExpense));
                         break;
@@ -201,6 +271,12 @@ function createInput() {
                     case AccountType.mortgage:
                         current.accounts[id] = JSON.parse(JSON.stringify(defaultMortgage));
                         break;
+                    case AccountType.pension:
+                        current.accounts[id] = JSON.parse(JSON.stringify(defaultPension));
+                        break;
+                    case AccountType.realEstate:
+                        current.accounts[id] = JSON.parse(JSON.stringify(defaultRealEstate));
+                        break;
                     case AccountType.retirement:
                         current.accounts[id] = JSON.parse(JSON.stringify(defaultRetirement));
                         break;
@@ -274,6 +350,59 @@ function createSummaryData() {
 }
 export const summary_data = createSummaryData();
 
+// Years an early withdrawal penalty was paid in
+export const penalty_years = writable<number[]>([]);
+
+// Payoff date and interest totals of each account with an amortization schedule (keyed by uuid)
+export const amortization_summaries = writable<{[uuid: string]: AmortizationSummary}>({});
+
+
+function createAnalysisError() {
+	const { subscribe, set } = writable<SimulationError | null>(null);
+
+	return {
+		subscribe,
+        set: (input: SimulationError) => set(input),
+		reset: () => set(null)
+	};
+}
+export const analysis_error = createAnalysisError();
+
+
+function createMonteCarloData() {
+	const { subscribe, set } = writable<MonteCarloResult | null>(null);
+
+	return {
+		subscribe,
+        set: (input: MonteCarloResult) => set(input),
+		reset: () => set(null)
+	};
+}
+export const monte_carlo_data = createMonteCarloData();
+
+
+function createBacktestData() {
+	const { subscribe, set } = writable<BacktestResult | null>(null);
+
+	return {
+		subscribe,
+        set: (input: BacktestResult) => set(input),
+		reset: () => set(null)
+	};
+}
+export const backtest_data = createBacktestData();
+
+function createConversionData() {
+	const { subscribe, set } = writable<ConversionComparison | null>(null);
+
+	return {
+		subscribe,
+        set: (input: ConversionComparison) => set(input),
+		reset: () => set(null)
+	};
+}
+export const conversion_data = createConversionData();
+
 export const dark = writable(false);
 
 